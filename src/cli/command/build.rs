@@ -1,4 +1,5 @@
 use clap::Args;
+use crate::compiler::MessageFormat;
 
 #[derive(Args)]
 pub struct BuildCommand {
@@ -7,4 +8,54 @@ pub struct BuildCommand {
         action = clap::ArgAction::Append,
     )]
     pub units: Option<Vec<String>>,
+    
+    /// Specify the number of source files to compile in parallel
+    /// 
+    /// Defaults to the number of available CPUs
+    #[arg(
+        long, short,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub jobs: Option<usize>,
+    
+    /// Specify the build profile to use
+    /// 
+    /// Built-in profiles are "debug" and "release", while custom ones can be defined in the
+    /// project configuration
+    #[arg(
+        long,
+        default_value = "debug"
+    )]
+    pub profile: String,
+    
+    /// Generate a compilation database (compile_commands.json) at the project root
+    #[arg(
+        long
+    )]
+    pub compile_commands: bool,
+    
+    /// Continue building after a failure and summarize all the failures at the end
+    /// 
+    /// Only the units depending on the failed ones are skipped
+    #[arg(
+        long
+    )]
+    pub keep_going: bool,
+    
+    /// Print the commands which would be executed without executing them
+    #[arg(
+        long
+    )]
+    pub dry_run: bool,
+    
+    /// Specify the format of the reported messages
+    /// 
+    /// "json" prints every compiler diagnostic and the result of every unit as a separate JSON
+    /// object per line
+    #[arg(
+        long,
+        default_value = "human",
+        value_parser = MessageFormat::str_variants()
+    )]
+    pub message_format: String,
 }
\ No newline at end of file
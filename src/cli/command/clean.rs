@@ -0,0 +1,24 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct CleanCommand {
+    /// Specify the units to clean
+    #[arg(
+        action = clap::ArgAction::Append,
+    )]
+    pub units: Option<Vec<String>>,
+    
+    /// Specify the build profile to clean
+    /// 
+    /// Artifacts of all the profiles are removed if not specified
+    #[arg(
+        long
+    )]
+    pub profile: Option<String>,
+    
+    /// Only list the files which would be removed without removing them
+    #[arg(
+        long
+    )]
+    pub dry_run: bool,
+}
@@ -0,0 +1,17 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct CompdbCommand {
+    /// Specify the units to include in the compilation database
+    #[arg(
+        action = clap::ArgAction::Append,
+    )]
+    pub units: Option<Vec<String>>,
+    
+    /// Specify the build profile which compile commands are generated for
+    #[arg(
+        long,
+        default_value = "debug"
+    )]
+    pub profile: String,
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+use clap::Args;
+
+#[derive(Args)]
+pub struct InstallCommand {
+    /// Specify the units to install
+    #[arg(
+        action = clap::ArgAction::Append,
+    )]
+    pub units: Option<Vec<String>>,
+    
+    /// Specify the directory to install the units into
+    #[arg(
+        long,
+        default_value = "/usr/local"
+    )]
+    pub prefix: PathBuf,
+    
+    /// Specify the staging directory which the prefix is placed under
+    #[arg(
+        long
+    )]
+    pub destdir: Option<PathBuf>,
+    
+    /// Specify the build profile to use
+    #[arg(
+        long,
+        default_value = "release"
+    )]
+    pub profile: String,
+}
+
+#[derive(Args)]
+pub struct UninstallCommand {}
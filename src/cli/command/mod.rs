@@ -1,6 +1,11 @@
 mod init;
 mod build;
 mod new;
+mod compdb;
+mod clean;
+mod run;
+mod test;
+mod install;
 
 use std::path::PathBuf;
 use clap::{Parser, Subcommand};
@@ -15,6 +20,22 @@ pub struct Cli {
         global = true,
     )]
     pub location: Option<PathBuf>,
+    
+    /// Print only errors and warnings
+    #[arg(
+        long, short,
+        global = true,
+        conflicts_with = "verbose",
+    )]
+    pub quiet: bool,
+    
+    /// Print the executed commands (-vv to also print their working directory and environment)
+    #[arg(
+        long, short,
+        global = true,
+        action = clap::ArgAction::Count,
+    )]
+    pub verbose: u8,
 
     #[command(subcommand)]
     pub command: Commands,
@@ -30,4 +51,22 @@ pub enum Commands {
 
     /// Create a new Copper project
     New(new::NewCommand),
+    
+    /// Generate a compilation database (compile_commands.json) without building the project
+    Compdb(compdb::CompdbCommand),
+    
+    /// Remove the build artifacts of the local Copper project
+    Clean(clean::CleanCommand),
+    
+    /// Build and execute a binary unit of the local Copper project
+    Run(run::RunCommand),
+    
+    /// Build and run the test units of the local Copper project
+    Test(test::TestCommand),
+    
+    /// Build and install the units of the local Copper project
+    Install(install::InstallCommand),
+    
+    /// Remove the files installed by the last installations of the local Copper project
+    Uninstall(install::UninstallCommand),
 }
\ No newline at end of file
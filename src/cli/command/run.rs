@@ -0,0 +1,31 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct RunCommand {
+    /// Specify the binary unit to run
+    /// 
+    /// Defaults to the project's "default-run" unit or the only binary unit of the project
+    pub unit: Option<String>,
+    
+    /// Specify the number of source files to compile in parallel
+    /// 
+    /// Defaults to the number of available CPUs
+    #[arg(
+        long, short,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub jobs: Option<usize>,
+    
+    /// Specify the build profile to use
+    #[arg(
+        long,
+        default_value = "debug"
+    )]
+    pub profile: String,
+    
+    /// Arguments passed to the executed unit
+    #[arg(
+        last = true
+    )]
+    pub args: Vec<String>,
+}
@@ -0,0 +1,33 @@
+use clap::Args;
+
+#[derive(Args)]
+pub struct TestCommand {
+    /// Run only the test units whose names contain the filter
+    pub filter: Option<String>,
+    
+    /// Specify the number of source files to compile and tests to run in parallel
+    /// 
+    /// Defaults to the number of available CPUs
+    #[arg(
+        long, short,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub jobs: Option<usize>,
+    
+    /// Specify the build profile to use
+    #[arg(
+        long,
+        default_value = "debug"
+    )]
+    pub profile: String,
+    
+    /// Specify the time in seconds a test is allowed to run before it is considered failed
+    /// 
+    /// Applies to the test units which don't specify their own timeout
+    #[arg(
+        long,
+        default_value = "60",
+        value_parser = clap::builder::RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub timeout: u64,
+}
@@ -1,10 +1,13 @@
 use std::env;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use clap::ArgMatches;
 use crate::jobs;
+use crate::compiler::MessageFormat;
+use crate::error::Result;
 use crate::config::{ProjectLanguage, UnitType};
 
-pub fn handle_init(matches: &ArgMatches) {
+pub fn handle_init(matches: &ArgMatches) -> Result<()> {
     let project_language = {
         let language_str = matches.get_one::<String>("language").unwrap();
         // Safe to unwrap as we already checked for valid enum strings
@@ -30,18 +33,101 @@ pub fn handle_init(matches: &ArgMatches) {
 
     let generate_example = matches.get_flag("example") && !matches.get_flag("minimal");
 
-    jobs::init(project_location, project_name, project_language, generate_example);
+    jobs::init(project_location, project_name, project_language, generate_example)
 }
 
-pub fn handle_build(matches: &ArgMatches) {
+pub fn handle_build(matches: &ArgMatches) -> Result<()> {
     let units = matches.get_many::<String>("units");
     
     let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    let profile_name = matches.get_one::<String>("profile").unwrap();
+    
+    let settings = jobs::BuildSettings {
+        jobs: matches.get_one::<usize>("jobs").copied(),
+        message_format: MessageFormat::from(matches.get_one::<String>("message_format").unwrap().as_str()),
+        keep_going: matches.get_flag("keep_going"),
+        dry_run: matches.get_flag("dry_run"),
+    };
+    
+    let compile_commands = matches.get_flag("compile_commands");
 
-    jobs::build(units, project_location);
+    jobs::build(units, project_location, profile_name, settings, compile_commands)
 }
 
-pub fn handle_new(matches: &ArgMatches) {
+pub fn handle_compdb(matches: &ArgMatches) -> Result<()> {
+    let units = matches.get_many::<String>("units");
+    
+    let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    let profile_name = matches.get_one::<String>("profile").unwrap();
+
+    jobs::compdb(units, project_location, profile_name)
+}
+
+pub fn handle_clean(matches: &ArgMatches) -> Result<()> {
+    let units = matches.get_many::<String>("units");
+    
+    let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    let profile_name = matches.get_one::<String>("profile").map(String::as_str);
+    
+    let dry_run = matches.get_flag("dry_run");
+
+    jobs::clean(units, project_location, profile_name, dry_run)
+}
+
+pub fn handle_run(matches: &ArgMatches) -> Result<ExitCode> {
+    let unit_name = matches.get_one::<String>("unit").map(String::as_str);
+    
+    let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    let profile_name = matches.get_one::<String>("profile").unwrap();
+    
+    let jobs = matches.get_one::<usize>("jobs").copied();
+    
+    let args = matches.get_many::<String>("args")
+        .map(|args| args.cloned().collect())
+        .unwrap_or_default();
+
+    jobs::run(unit_name, project_location, profile_name, jobs, args)
+}
+
+pub fn handle_test(matches: &ArgMatches) -> Result<ExitCode> {
+    let filter = matches.get_one::<String>("filter").map(String::as_str);
+    
+    let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    let profile_name = matches.get_one::<String>("profile").unwrap();
+    
+    let jobs = matches.get_one::<usize>("jobs").copied();
+    
+    let timeout = *matches.get_one::<u64>("timeout").unwrap();
+
+    jobs::test(filter, project_location, profile_name, jobs, timeout)
+}
+
+pub fn handle_install(matches: &ArgMatches) -> Result<()> {
+    let units = matches.get_many::<String>("units");
+    
+    let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    let profile_name = matches.get_one::<String>("profile").unwrap();
+    
+    let prefix = matches.get_one::<PathBuf>("prefix").unwrap();
+    
+    let destdir = matches.get_one::<PathBuf>("destdir").map(PathBuf::as_path);
+
+    jobs::install(units, project_location, profile_name, prefix, destdir)
+}
+
+pub fn handle_uninstall(matches: &ArgMatches) -> Result<()> {
+    let project_location = matches.get_one::<PathBuf>("location").unwrap();
+    
+    jobs::uninstall(project_location)
+}
+
+pub fn handle_new(matches: &ArgMatches) -> Result<()> {
     let project_location = matches.get_one::<PathBuf>("location").unwrap();
  
     if let Some(matches) = matches.subcommand_matches("unit") {
@@ -54,6 +140,8 @@ pub fn handle_new(matches: &ArgMatches) {
             UnitType::try_from(type_str.to_string()).unwrap()
         };
 
-        jobs::new::new_unit(project_location, unit_name, unit_type.clone(), unit_path.clone());
+        jobs::new::new_unit(project_location, unit_name, unit_type.clone(), unit_path.clone())?;
     }
+    
+    Ok(())
 }
\ No newline at end of file
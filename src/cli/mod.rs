@@ -1,18 +1,33 @@
+use std::process::ExitCode;
 use clap::ArgMatches;
+use crate::error::Result;
+use crate::output::{self, Verbosity};
 
 pub mod command;
 mod handlers;
 
-pub fn match_args(matches: ArgMatches) {
-    if let Some(matches) = matches.subcommand_matches("init") {
-        handlers::handle_init(matches);
-    }
-
-    if let Some(matches) = matches.subcommand_matches("build") {
-        handlers::handle_build(matches);
-    }
-
-    if let Some(matches) = matches.subcommand_matches("new") {
-        handlers::handle_new(matches);
+pub fn match_args(matches: ArgMatches) -> Result<ExitCode> {
+    let verbosity = match (matches.get_flag("quiet"), matches.get_count("verbose")) {
+        (true, _) => Verbosity::Quiet,
+        (false, 0) => Verbosity::Normal,
+        (false, 1) => Verbosity::Verbose,
+        (false, _) => Verbosity::VeryVerbose,
+    };
+    output::set_verbosity(verbosity);
+    
+    match matches.subcommand() {
+        Some(("init", matches)) => handlers::handle_init(matches)?,
+        Some(("build", matches)) => handlers::handle_build(matches)?,
+        Some(("new", matches)) => handlers::handle_new(matches)?,
+        Some(("compdb", matches)) => handlers::handle_compdb(matches)?,
+        Some(("clean", matches)) => handlers::handle_clean(matches)?,
+        Some(("install", matches)) => handlers::handle_install(matches)?,
+        Some(("uninstall", matches)) => handlers::handle_uninstall(matches)?,
+        // Run forwards the exit code of the executed unit, while test reports failed tests
+        Some(("run", matches)) => return handlers::handle_run(matches),
+        Some(("test", matches)) => return handlers::handle_test(matches),
+        _ => {}
     }
+    
+    Ok(ExitCode::SUCCESS)
 }
\ No newline at end of file
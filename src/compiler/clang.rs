@@ -0,0 +1,7 @@
+//! Contains Clang-specific implementations
+
+use crate::compiler::command::CompilerCommandFlags;
+use crate::compiler::gcc;
+
+/// Clang is compatible with GCC's command line options
+pub const FLAGS: CompilerCommandFlags = gcc::FLAGS;
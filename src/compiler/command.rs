@@ -1,46 +1,91 @@
 use std::process::{Command, Output};
 use std::path::{Path, PathBuf};
-use std::{fs, io};
+use std::io;
 use std::borrow::Cow;
-use std::ffi::OsString;
+use std::env;
 use crate::config::ProjectLanguage;
 
+/// Inherited environment variables which affect the behaviour of the compiler toolchain
+const TOOLCHAIN_VARIABLES: [&str; 7] = [
+    "PATH", "CPATH", "C_INCLUDE_PATH", "CPLUS_INCLUDE_PATH", "LIBRARY_PATH", "GCC_EXEC_PREFIX", "COMPILER_PATH",
+];
+
 /// Specifies the compiler-specific option flags
 pub struct CompilerCommandFlags {
     pub output: &'static str,
     pub compile: &'static str,
     pub include: &'static str,
+    pub define: &'static str,
+    pub optimization: &'static str,
+    pub debug_info: &'static str,
     pub language: &'static str,
+    pub dependencies: &'static str,
+    pub dependency_file: &'static str,
+    pub archive: &'static str,
+    pub position_independent: &'static str,
+    pub shared: &'static str,
+    pub soname: &'static str,
+    pub runtime_path: &'static str,
+    pub runtime_origin: &'static str,
+    pub parseable_fixits: &'static str,
+    pub library_path: &'static str,
+    pub link_library: &'static str,
+    pub whole_archive: &'static str,
+    pub no_whole_archive: &'static str,
+    /// Links the whole static library on macOS, whose linker doesn't support the whole archive
+    /// mode
+    pub force_load: &'static str,
 }
 
 /// Wrapper for the compiler command executor
 pub struct CompilerCommand {
     executable_name: String,
+    /// Linker drivers for targets consisting of C objects only and for targets containing C++
+    /// objects
+    c_linker_name: String,
+    cpp_linker_name: String,
+    archiver_name: String,
     command_flags: CompilerCommandFlags,
     root_relative_path: PathBuf,
     include_paths: Vec<PathBuf>,
+    /// Project-wide arguments passed only to the compiler
     additional_args: Vec<String>,
+    /// Arguments passed only to the compiler
+    compile_args: Vec<String>,
+    /// Arguments passed only to the linker
+    link_args: Vec<String>,
 }
 
 impl CompilerCommand {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         executable_name: String,
+        c_linker_name: String,
+        cpp_linker_name: String,
+        archiver_name: String,
         command_flags: CompilerCommandFlags,
         root_relative_path: PathBuf,
         include_paths: Vec<PathBuf>,
         additional_args: Vec<String>,
+        compile_args: Vec<String>,
+        link_args: Vec<String>,
     ) -> Self {
         CompilerCommand {
             executable_name,
+            c_linker_name,
+            cpp_linker_name,
+            archiver_name,
             command_flags,
             root_relative_path,
             include_paths,
             additional_args,
+            compile_args,
+            link_args,
         }
     }
 
     /// Initiates a new Executor to use
-    pub fn executor<'a>(&self) -> io::Result<CompilerCommandExecutor> {
+    pub fn executor(&self) -> io::Result<CompilerCommandExecutor<'_>> {
         let mut executor = CompilerCommandExecutor::new(
             &self.executable_name,
             &self.command_flags,
@@ -49,9 +94,39 @@ impl CompilerCommand {
         
         self.include_paths.iter().try_for_each(|p| executor.add_include_path(p))?;
         self.additional_args.iter().for_each(|a| executor.add_arg(a));
+        self.compile_args.iter().for_each(|a| executor.add_arg(a));
         
         Ok(executor)
     }
+    
+    /// Initiates a new Executor for the linker driver of the language. Include paths and compiler
+    /// arguments are not passed to the linker
+    pub fn linker(&self, language: &ProjectLanguage) -> CompilerCommandExecutor<'_> {
+        let linker_name = match language {
+            ProjectLanguage::C => &self.c_linker_name,
+            ProjectLanguage::CPP => &self.cpp_linker_name,
+        };
+        CompilerCommandExecutor::new(
+            linker_name,
+            &self.command_flags,
+            &self.root_relative_path,
+        )
+    }
+    
+    /// Returns the arguments passed only to the linker
+    pub fn link_args(&self) -> &[String] {
+        &self.link_args
+    }
+    
+    /// Initiates a new Executor for the static library archiver. Include paths and additional
+    /// arguments are not passed to the archiver
+    pub fn archiver(&self) -> CompilerCommandExecutor<'_> {
+        CompilerCommandExecutor::new(
+            &self.archiver_name,
+            &self.command_flags,
+            &self.root_relative_path,
+        )
+    }
 }
 
 /// Executor for the compiler command itself
@@ -77,29 +152,71 @@ impl<'a> CompilerCommandExecutor<'a> {
     /// Add the compile flag to the compiler command
     pub fn set_compile_flag(&mut self) {
         self.command
-            .arg(&self.flags.compile);
+            .arg(self.flags.compile);
+    }
+    
+    /// Make the compiler generate position-independent code (required for dynamic libraries)
+    pub fn set_position_independent(&mut self) {
+        self.command
+            .arg(self.flags.position_independent);
+    }
+    
+    /// Make the linker produce a shared library
+    pub fn set_shared(&mut self) {
+        self.command
+            .arg(self.flags.shared);
+    }
+    
+    /// Specify the soname of the shared library for the linker
+    pub fn set_soname(&mut self, soname: &str) {
+        self.command
+            .arg(format!("{}{}", self.flags.soname, soname));
+    }
+    
+    /// Add a runtime library search path relative to the location of the output file
+    pub fn add_runtime_library_path(&mut self, library_path: &Path) {
+        let mut path = PathBuf::from(self.flags.runtime_origin);
+        if !library_path.as_os_str().is_empty() {
+            path.push(library_path);
+        }
+        
+        self.command
+            .arg(format!("{}{}", self.flags.runtime_path, path.display()));
     }
     
     /// Specify the language for the compiler
     pub fn set_language(&mut self, language: &ProjectLanguage) {
         self.command
-            .arg(&self.flags.language)
+            .arg(self.flags.language)
             .arg(language.to_string());
     }
 
-    /// Specify the output file and make sure all the parent directories exist
-    pub fn set_output_file(&mut self, output_file: &Path) -> io::Result<()> {
-        if let Some(output_dir) = output_file.parent() {
-            if output_dir.is_dir() && !output_dir.exists() {
-                fs::create_dir_all(output_dir)?;
-            }
-        }
-
+    /// Specify the output file
+    pub fn set_output_file(&mut self, output_file: &Path) {
         self.command
-            .arg(&self.flags.output)
+            .arg(self.flags.output)
             .arg(output_file);
-
-        Ok(())
+    }
+    
+    /// Specify the static library file to be created by the archiver
+    pub fn set_archive_file(&mut self, archive_file: &Path) {
+        self.command
+            .arg(self.flags.archive)
+            .arg(archive_file);
+    }
+    
+    /// Make the compiler report fix-it hints in a machine-readable form
+    pub fn set_parseable_fixits(&mut self) {
+        self.command
+            .arg(self.flags.parseable_fixits);
+    }
+    
+    /// Make the compiler generate a dependency file listing all the included user headers
+    pub fn set_dependency_file(&mut self, dependency_file: &Path) {
+        self.command
+            .arg(self.flags.dependencies)
+            .arg(self.flags.dependency_file)
+            .arg(dependency_file);
     }
     
     /// Add an include path to the compiler command and verify that it exists
@@ -114,12 +231,56 @@ impl<'a> CompilerCommandExecutor<'a> {
         }
         
         self.command
-            .arg(&self.flags.include)
+            .arg(self.flags.include)
             .arg(include_path);
         
         Ok(())
     }
 
+    /// Add a preprocessor definition (`NAME` or `NAME=VALUE`) to the compiler command. The
+    /// definition is passed as a single argument, so its value doesn't need any shell quoting
+    pub fn add_define(&mut self, define: &str) {
+        self.command
+            .arg(format!("{}{}", self.flags.define, define));
+    }
+
+    /// Add a library search path to the linker command and verify that it exists
+    pub fn add_library_path(&mut self, library_path: &Path) -> io::Result<()> {
+        let library_path = self.relative_path.join(library_path);
+        
+        if !library_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound, 
+                format!("Library path '{}' does not exist", library_path.display())
+            ));
+        }
+        
+        self.command
+            .arg(format!("{}{}", self.flags.library_path, library_path.display()));
+        
+        Ok(())
+    }
+    
+    /// Add a library which the linker searches by name in the library paths
+    pub fn add_link_library(&mut self, name: &str) {
+        self.command
+            .arg(format!("{}{}", self.flags.link_library, name));
+    }
+    
+    /// Add a static library to the linker command whose objects are all linked in, including the
+    /// ones which are not referenced by the other objects
+    pub fn add_whole_archive(&mut self, archive: &Path) {
+        if cfg!(target_os = "macos") {
+            self.command
+                .arg(format!("{}{}", self.flags.force_load, archive.display()));
+        } else {
+            self.command
+                .arg(self.flags.whole_archive)
+                .arg(archive)
+                .arg(self.flags.no_whole_archive);
+        }
+    }
+
     /// Add an input file to the compiler command and verify that it exists
     pub fn add_input_file(&mut self, source_file: &Path) -> io::Result<()> {
         if !source_file.exists() {
@@ -140,15 +301,73 @@ impl<'a> CompilerCommandExecutor<'a> {
         self.command.arg(arg);
     }
 
-    /// Consumes itself and spawns the process, waits for its completion and returns the output
-    pub fn execute(mut self) -> io::Result<Output> {
+    /// Returns the executable followed by all the arguments of the command
+    pub fn arguments(&self) -> Vec<String> {
+        std::iter::once(self.command.get_program())
+            .chain(self.command.get_args())
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+    
+    /// Returns the executable followed by the arguments which affect the command's output, which
+    /// are all of them except the ones that only change how diagnostics are reported
+    pub fn output_arguments(&self) -> Vec<String> {
+        self.arguments().into_iter()
+            .filter(|arg| arg != self.flags.parseable_fixits)
+            .collect()
+    }
+    
+    /// Returns the full command line which is going to be executed. Arguments are quoted where
+    /// needed, so the command line can be pasted into a shell
+    pub fn command_line(&self) -> String {
         let cmd_str = self.command.get_program().to_string_lossy();
         let args_str = self.command.get_args()
-            .map(|arg| arg.to_string_lossy())
+            .map(|arg| quote_arg(arg.to_string_lossy()))
             .collect::<Vec<Cow<str>>>()
             .join(" ");
         
-        println!("Executing: {} {}", cmd_str, args_str);
+        format!("{} {}", cmd_str, args_str)
+    }
+
+    /// Returns the full command line together with the working directory and the environment
+    /// variables (affecting the toolchain) it is going to be executed with
+    pub fn describe(&self) -> String {
+        let working_directory = match self.command.get_current_dir() {
+            Some(dir) => dir.to_path_buf(),
+            None => env::current_dir().unwrap_or_default(),
+        };
+        
+        let mut description = format!("{}\n  working directory: {}", self.command_line(), working_directory.display());
+        for name in TOOLCHAIN_VARIABLES {
+            if let Ok(value) = env::var(name) {
+                description.push_str(&format!("\n  {}={}", name, value));
+            }
+        }
+        for (name, value) in self.command.get_envs() {
+            description.push_str(&format!(
+                "\n  {}={}",
+                name.to_string_lossy(),
+                value.map(|value| value.to_string_lossy()).unwrap_or_default()
+            ));
+        }
+        
+        description
+    }
+
+    /// Consumes itself and spawns the process, waits for its completion and returns the output
+    pub fn execute(mut self) -> io::Result<Output> {
         self.command.output()
     }
-}
\ No newline at end of file
+}
+
+/// Quotes the argument for a POSIX shell, unless it consists only of characters which are safe to
+/// use unquoted
+fn quote_arg(arg: Cow<str>) -> Cow<str> {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-+=/.,:@%^".contains(c);
+    
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        arg
+    } else {
+        Cow::Owned(format!("'{}'", arg.replace('\'', "'\\''")))
+    }
+}
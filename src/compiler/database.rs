@@ -0,0 +1,29 @@
+//! Contains types for generating a JSON compilation database (`compile_commands.json`), which is
+//! used by editors and tools such as clangd and clang-tidy
+
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use crate::file;
+
+/// Name of the compilation database file
+pub const COMPILATION_DATABASE_FILE_NAME: &str = "compile_commands.json";
+
+/// A single entry of the compilation database describing how a translation unit is compiled
+#[derive(Debug, Serialize)]
+pub struct CompileCommand {
+    /// Working directory of the compilation. All the other paths are relative to it
+    pub directory: PathBuf,
+    /// Main source file of the translation unit
+    pub file: PathBuf,
+    /// Compiler executable followed by all of its arguments
+    pub arguments: Vec<String>,
+    /// Object file produced by the compilation
+    pub output: PathBuf,
+}
+
+/// Writes the compilation database entries as a JSON array to the specified file
+pub fn write_compilation_database(path: &Path, commands: &[CompileCommand]) -> io::Result<()> {
+    let data = serde_json::to_string_pretty(commands)?;
+    file::write_file(path, data)
+}
@@ -0,0 +1,132 @@
+//! Contains functions for working with compiler-generated dependency files (depfiles), which are
+//! used to determine whether object files need to be recompiled
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Checks whether the object file is up to date with its source file and all the headers listed
+/// in the dependency file. Any missing or unreadable file is treated as outdated
+pub fn is_up_to_date(object_file: &Path, source_file: &Path, dependency_file: &Path) -> bool {
+    let object_time = match modified_time(object_file) {
+        Ok(time) => time,
+        Err(_) => return false,
+    };
+
+    let dependencies = match read_dependency_file(dependency_file) {
+        Ok(dependencies) => dependencies,
+        Err(_) => return false,
+    };
+
+    std::iter::once(source_file.to_path_buf())
+        .chain(dependencies)
+        .all(|dependency| match modified_time(&dependency) {
+            Ok(time) => time <= object_time,
+            Err(_) => false,
+        })
+}
+
+/// Checks whether the output file exists and is newer than every one of the input files
+pub fn is_newer_than_all(output_file: &Path, input_files: &[PathBuf]) -> bool {
+    let output_time = match modified_time(output_file) {
+        Ok(time) => time,
+        Err(_) => return false,
+    };
+
+    input_files.iter().all(|file| match modified_time(file) {
+        Ok(time) => time <= output_time,
+        Err(_) => false,
+    })
+}
+
+/// Returns the last modification time of the file
+pub fn modified_time(path: &Path) -> io::Result<SystemTime> {
+    fs::metadata(path)?.modified()
+}
+
+/// Reads a Make-style dependency file (as generated by `-MMD -MF`) and returns the list of files
+/// the target depends on
+pub fn read_dependency_file(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let data = fs::read_to_string(path)?;
+    Ok(parse_dependency_rule(&data))
+}
+
+/// Parses the prerequisites of the first rule in a Make-style dependency file. Handles line
+/// continuations and escaped spaces in file names
+fn parse_dependency_rule(data: &str) -> Vec<PathBuf> {
+    let data = data.replace("\\\r\n", " ").replace("\\\n", " ");
+    let rule = data.lines().next().unwrap_or("");
+
+    // The target may contain a drive letter on Windows (e.g. "C:\obj\main.o"), so the separator is
+    // the first colon followed by whitespace or the end of the line
+    let prerequisites = match rule.char_indices()
+        .find(|(i, c)| *c == ':' && rule[i + 1..].chars().next().is_none_or(char::is_whitespace))
+    {
+        Some((i, _)) => &rule[i + 1..],
+        None => return Vec::new(),
+    };
+
+    let mut dependencies = Vec::new();
+    let mut current = String::new();
+    let mut chars = prerequisites.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&' ') => {
+                current.push(' ');
+                chars.next();
+            },
+            '$' if chars.peek() == Some(&'$') => {
+                current.push('$');
+                chars.next();
+            },
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    dependencies.push(PathBuf::from(&current));
+                    current.clear();
+                }
+            },
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        dependencies.push(PathBuf::from(current));
+    }
+
+    dependencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn parses_continued_rule() {
+        let data = "build/obj/main.c.o: src/main.c src/include/util.h \\\n  src/include/net.h\n\nsrc/include/util.h:\n";
+        assert_eq!(parse_dependency_rule(data), paths(&["src/main.c", "src/include/util.h", "src/include/net.h"]));
+        
+        let data = "main.o: main.c \\\r\n util.h\r\n";
+        assert_eq!(parse_dependency_rule(data), paths(&["main.c", "util.h"]));
+    }
+
+    #[test]
+    fn parses_escaped_file_names() {
+        let data = "main.o: my\\ dir/main.c cost$$.h";
+        assert_eq!(parse_dependency_rule(data), paths(&["my dir/main.c", "cost$.h"]));
+    }
+
+    #[test]
+    fn parses_windows_target() {
+        let data = "C:\\obj\\main.o: C:\\src\\main.c";
+        assert_eq!(parse_dependency_rule(data), paths(&["C:\\src\\main.c"]));
+        
+        assert_eq!(parse_dependency_rule("main.o:"), paths(&[]));
+        assert_eq!(parse_dependency_rule(""), paths(&[]));
+    }
+}
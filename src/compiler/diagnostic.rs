@@ -0,0 +1,241 @@
+//! Parsing of the diagnostics reported by GCC-compatible compilers
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use serde::Serialize;
+
+/// Severity of the compiler diagnostic
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// Single diagnostic reported by the compiler together with the notes attached to it
+#[derive(Debug, Serialize, Clone)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixits: Vec<FixIt>,
+}
+
+/// Suggested replacement of the source code range (lines and columns are 1-based, the end column
+/// is exclusive)
+#[derive(Debug, Serialize, Clone)]
+pub struct FixIt {
+    pub file: PathBuf,
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    pub replacement: String,
+}
+
+impl Diagnostic {
+    /// Parses the compiler output in the GCC/Clang text format
+    /// (`file:line:column: severity: message`). Notes and fix-its (reported with
+    /// `-fdiagnostics-parseable-fixits`) are attached to the preceding error or warning, while
+    /// lines which are not diagnostics (such as the source code snippets) are skipped
+    pub fn parse(output: &str) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        
+        for line in output.lines() {
+            if let Some(fixit) = FixIt::parse(line) {
+                if let Some(parent) = diagnostics.last_mut() {
+                    parent.fixits.push(fixit);
+                }
+                continue;
+            }
+            
+            let Some(diagnostic) = Self::parse_line(line) else { continue };
+            
+            match (diagnostic.severity, diagnostics.last_mut()) {
+                (Severity::Note, Some(parent)) => parent.notes.push(diagnostic),
+                _ => diagnostics.push(diagnostic),
+            }
+        }
+        
+        diagnostics
+    }
+    
+    /// Parses a single `file:line:column: severity: message` line
+    fn parse_line(line: &str) -> Option<Diagnostic> {
+        const SEVERITIES: [(&str, Severity); 4] = [
+            (": fatal error: ", Severity::Error),
+            (": error: ", Severity::Error),
+            (": warning: ", Severity::Warning),
+            (": note: ", Severity::Note),
+        ];
+        
+        let (index, marker, severity) = SEVERITIES.iter()
+            .filter_map(|(marker, severity)| line.find(marker).map(|index| (index, *marker, *severity)))
+            .min_by_key(|(index, _, _)| *index)?;
+        
+        let location = &line[..index];
+        let message = line[index + marker.len()..].to_string();
+        
+        // File names may contain colons themselves (e.g. Windows drive letters), so the location is
+        // split from the right
+        let mut parts = location.rsplitn(3, ':');
+        let last = parts.next()?;
+        let (file, line, column) = match (parts.next(), parts.next()) {
+            (Some(line), Some(file)) if line.parse::<u32>().is_ok() && last.parse::<u32>().is_ok() => {
+                (file, line.parse().ok(), last.parse().ok())
+            },
+            _ => match location.rsplit_once(':') {
+                Some((file, line)) if line.parse::<u32>().is_ok() => (file, line.parse().ok(), None),
+                _ => (location, None, None),
+            },
+        };
+        
+        Some(Diagnostic {
+            file: PathBuf::from(file),
+            line,
+            column,
+            severity,
+            message,
+            notes: Vec::new(),
+            fixits: Vec::new(),
+        })
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        };
+        write!(f, ": {}: {}", severity, self.message)
+    }
+}
+
+impl FixIt {
+    /// Parses a `fix-it:"file":{line:column-line:column}:"replacement"` line
+    fn parse(line: &str) -> Option<FixIt> {
+        let rest = line.strip_prefix("fix-it:")?;
+        let (file, rest) = parse_quoted(rest)?;
+        
+        let rest = rest.strip_prefix(":{")?;
+        let (range, rest) = rest.split_once("}:")?;
+        let (start, end) = range.split_once('-')?;
+        let (start_line, start_column) = start.split_once(':')?;
+        let (end_line, end_column) = end.split_once(':')?;
+        
+        let (replacement, _) = parse_quoted(rest)?;
+        
+        Some(FixIt {
+            file: PathBuf::from(file),
+            start_line: start_line.parse().ok()?,
+            start_column: start_column.parse().ok()?,
+            end_line: end_line.parse().ok()?,
+            end_column: end_column.parse().ok()?,
+            replacement,
+        })
+    }
+}
+
+/// Parses the C-style escaped string in double quotes at the beginning of the input. Returns the
+/// unescaped string and the rest of the input
+fn parse_quoted(input: &str) -> Option<(String, &str)> {
+    let mut chars = input.strip_prefix('"')?.char_indices();
+    let mut value = String::new();
+    
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => return Some((value, &input[index + 2..])),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                digit @ '0'..='7' => {
+                    // Octal escape sequence of up to three digits
+                    let mut code = digit.to_digit(8)?;
+                    for _ in 0..2 {
+                        match chars.clone().next() {
+                            Some((_, digit @ '0'..='7')) => {
+                                code = code * 8 + digit.to_digit(8)?;
+                                chars.next();
+                            },
+                            _ => break,
+                        }
+                    }
+                    value.push(char::from_u32(code)?);
+                },
+                other => value.push(other),
+            },
+            other => value.push(other),
+        }
+    }
+    
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_diagnostics_with_notes_and_fixits() {
+        let output = "\
+src/main.c: In function 'main':
+src/main.c:4:5: warning: implicit declaration of function 'foo' [-Wimplicit-function-declaration]
+    4 |     foo();
+      |     ^~~
+src/main.c:7:12: error: expected ';' before '}' token
+fix-it:\"src/main.c\":{7:12-7:12}:\";\"
+src/util.h:2:6: note: previous declaration here
+C:\\project\\lib.c:3: fatal error: missing.h: No such file or directory
+cc1: error: unrecognized option
+";
+        let diagnostics = Diagnostic::parse(output);
+        assert_eq!(diagnostics.len(), 4);
+        
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].to_string(), "src/main.c:4:5: warning: implicit declaration of function 'foo' [-Wimplicit-function-declaration]");
+        
+        let error = &diagnostics[1];
+        assert_eq!((error.line, error.column), (Some(7), Some(12)));
+        assert_eq!(error.message, "expected ';' before '}' token");
+        assert_eq!(error.fixits.len(), 1);
+        assert_eq!(error.fixits[0].replacement, ";");
+        assert_eq!(error.notes.len(), 1);
+        assert_eq!(error.notes[0].file, PathBuf::from("src/util.h"));
+        
+        let fatal = &diagnostics[2];
+        assert_eq!(fatal.file, PathBuf::from("C:\\project\\lib.c"));
+        assert_eq!((fatal.line, fatal.column), (Some(3), None));
+        assert_eq!(fatal.severity, Severity::Error);
+        
+        assert_eq!(diagnostics[3].file, PathBuf::from("cc1"));
+        assert_eq!(diagnostics[3].line, None);
+    }
+
+    #[test]
+    fn parses_fixits() {
+        let fixit = FixIt::parse(r#"fix-it:"dir/a \"b\".c":{10:3-11:8}:"x\n\tq\101""#).unwrap();
+        assert_eq!(fixit.file, PathBuf::from("dir/a \"b\".c"));
+        assert_eq!((fixit.start_line, fixit.start_column, fixit.end_line, fixit.end_column), (10, 3, 11, 8));
+        assert_eq!(fixit.replacement, "x\n\tqA");
+        
+        assert!(FixIt::parse(r#"fix-it:"a.c":{1:2}:"x""#).is_none());
+        assert!(FixIt::parse(r#"fix-it:"a.c":{1:2-1:3}:"unterminated"#).is_none());
+        assert!(FixIt::parse("a.c:1:2: error: message").is_none());
+    }
+}
@@ -1,33 +1,63 @@
+//! Compiler specific error types and implementations
+
 use std::fmt::{Display, Formatter};
-use std::io;
+use std::path::PathBuf;
 use std::process::Output;
 use crate::error::parse_output;
+use super::diagnostic::{Diagnostic, Severity};
 
-pub type Result<T> = std::result::Result<T, Error>;
-
-/// GCC-specific error types
+/// Compiler-specific error types
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum Error {
-    /// Error related to the compilation of the source files
-    CompileError(Output),
-    /// Error related to the linking of the object files
-    LinkError(Output),
-    /// IO Error
-    IOError(String),
+    /// Error related to the compilation of the source files (with the failed command line)
+    CompileError(String, Output),
+    /// Error related to the linking of the object files (with the failed command line)
+    LinkError(String, Output),
+    /// Error related to the archiving of the object files into a static library (with the failed
+    /// command line)
+    ArchiveError(String, Output),
+    /// The selected compiler is not supported yet
+    UnsupportedCompiler(String),
+    /// The selected compiler's executables were not found on the current system
+    CompilerNotFound(String),
+    /// Multiple source files failed to compile (with the first error line of every file)
+    SourcesFailed(Vec<(PathBuf, String)>),
+    /// Multiple units failed to build (with their names)
+    UnitsFailed(Vec<String>),
 }
 
-impl Display for Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Error {
+    /// Returns a single line describing the error. For the failed commands it is the first error
+    /// reported by the compiler
+    pub fn first_line(&self) -> String {
         match self {
-            Error::CompileError(o) => write!(f, "{}", parse_output(o)),
-            Error::LinkError(o) => write!(f, "{}", parse_output(o)),
-            Error::IOError(s) => write!(f, "IO Error ({})", s),
-        }   
+            Error::CompileError(_, output) | Error::LinkError(_, output) | Error::ArchiveError(_, output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                
+                if let Some(diagnostic) = Diagnostic::parse(&stderr).iter().find(|d| d.severity == Severity::Error) {
+                    return diagnostic.to_string();
+                }
+                stderr.lines()
+                    .find(|line| !line.trim().is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| output.status.to_string())
+            },
+            _ => self.to_string(),
+        }
     }
 }
 
-impl From<io::Error> for Error {
-    fn from(err: io::Error) -> Self {
-        Error::IOError(err.to_string())
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::CompileError(c, o) => write!(f, "{}\n{}", c, parse_output(o)),
+            Error::LinkError(c, o) => write!(f, "{}\n{}", c, parse_output(o)),
+            Error::ArchiveError(c, o) => write!(f, "{}\n{}", c, parse_output(o)),
+            Error::UnsupportedCompiler(s) => write!(f, "Compiler '{}' is not supported yet", s),
+            Error::CompilerNotFound(s) => write!(f, "Compiler '{}' is not available on the current system", s),
+            Error::SourcesFailed(v) => write!(f, "{} source files failed to compile", v.len()),
+            Error::UnitsFailed(v) => write!(f, "Units failed to build ({})", v.join(", ")),
+        }
     }
-}
\ No newline at end of file
+}
@@ -6,5 +6,22 @@ pub const FLAGS: CompilerCommandFlags = CompilerCommandFlags {
     output: "-o",
     compile: "-c",
     include: "-I",
+    define: "-D",
+    optimization: "-O",
+    debug_info: "-g",
     language: "-x",
+    dependencies: "-MMD",
+    dependency_file: "-MF",
+    archive: "rcs",
+    position_independent: "-fPIC",
+    shared: "-shared",
+    soname: if cfg!(target_os = "macos") { "-Wl,-install_name,@rpath/" } else { "-Wl,-soname," },
+    runtime_path: "-Wl,-rpath,",
+    runtime_origin: if cfg!(target_os = "macos") { "@loader_path" } else { "$ORIGIN" },
+    parseable_fixits: "-fdiagnostics-parseable-fixits",
+    library_path: "-L",
+    link_library: "-l",
+    whole_archive: "-Wl,--whole-archive",
+    no_whole_archive: "-Wl,--no-whole-archive",
+    force_load: "-Wl,-force_load,",
 };
\ No newline at end of file
@@ -0,0 +1,7 @@
+//! Contains G++-specific implementations
+
+use crate::compiler::command::CompilerCommandFlags;
+use crate::compiler::gcc;
+
+/// G++ is a driver of the GNU Compiler Collection, so it shares the command line options with GCC
+pub const FLAGS: CompilerCommandFlags = gcc::FLAGS;
@@ -0,0 +1,68 @@
+//! Machine-readable messages reported during the build
+
+use std::path::Path;
+use serde::Serialize;
+use super::diagnostic::Diagnostic;
+
+/// Format of the messages reported during the build
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MessageFormat {
+    /// Human-readable text output
+    #[default]
+    Human,
+    /// One JSON object per line
+    Json,
+}
+
+impl MessageFormat {
+    const HUMAN_STR: &'static str = "human";
+    const JSON_STR: &'static str = "json";
+    
+    /// Returns an array of possible message format variants as stings
+    pub fn str_variants() -> [&'static str; 2] {
+        [Self::HUMAN_STR, Self::JSON_STR]
+    }
+}
+
+impl From<&str> for MessageFormat {
+    fn from(value: &str) -> Self {
+        match value {
+            Self::JSON_STR => MessageFormat::Json,
+            _ => MessageFormat::Human,
+        }
+    }
+}
+
+/// Message which is printed as a single JSON object with the `reason` field describing its kind
+#[allow(clippy::enum_variant_names)]
+#[derive(Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Message<'a> {
+    /// Diagnostic reported by the compiler while compiling the unit's source file
+    CompilerMessage {
+        unit: &'a str,
+        source: &'a Path,
+        diagnostic: &'a Diagnostic,
+    },
+    /// Result of building the unit
+    UnitFinished {
+        unit: &'a str,
+        success: bool,
+        /// Whether the unit's output file was already up to date
+        fresh: bool,
+        output: &'a Path,
+    },
+    /// Result of the whole build
+    BuildFinished {
+        success: bool,
+    },
+}
+
+impl Message<'_> {
+    /// Prints the message as a single line of JSON to the standard output
+    pub fn emit(&self) {
+        if let Ok(json) = serde_json::to_string(self) {
+            println!("{}", json);
+        }
+    }
+}
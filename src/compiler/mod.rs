@@ -1,113 +1,556 @@
-use std::process;
-use std::path::PathBuf;
-use command::CompilerCommand;
-use error::{Error, Result};
-use crate::config::{ProjectCompiler, ProjectLanguage, UnitType};
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::fmt::Display;
+use command::{CompilerCommand, CompilerCommandExecutor};
+use diagnostic::Diagnostic;
+use signature::SignatureStore;
+use crate::error::{Context, Result};
+use crate::file;
+use crate::output::{self, Verbosity};
+use crate::config::{LinkLibrary, ProfileConfig, ProjectCompiler, ProjectLanguage, UnitType, PROJECT_DIRECTORY_NAME};
 
 mod gcc;
+mod gpp;
+mod clang;
+mod dependency;
 mod util;
 mod command;
 mod error;
+mod database;
+mod diagnostic;
+mod message;
+mod signature;
+
+pub use error::Error;
+pub use util::available_jobs;
+pub use message::{Message, MessageFormat};
+pub use database::{CompileCommand, write_compilation_database, COMPILATION_DATABASE_FILE_NAME};
 
 /// An instance of a generic compiler which is responsible for building, compiling and linking
 /// project files
 pub struct Compiler {
     command: CompilerCommand,
-    compiler: ProjectCompiler,
-    language: ProjectLanguage,
+    /// Maximum number of source files compiled at the same time
+    jobs: usize,
+    message_format: MessageFormat,
+    /// Whether to keep compiling the remaining source files after a failure
+    keep_going: bool,
+    /// Whether to only print the commands instead of executing them
+    dry_run: bool,
+    /// Signatures of the commands which produced the object and output files
+    signatures: SignatureStore,
 }
 
 impl Compiler {
     /// Returns a specific compiler instance based on the selected project compiler
-    pub fn initialize(project_compiler: ProjectCompiler, options: CompilerOptions) -> Self {
-        if !util::check_if_available(&project_compiler) {
-            eprintln!("Unsupported compiler specified (Not available on the current system)");
-            process::exit(1);
-        }
-        
+    pub fn initialize(project_compiler: ProjectCompiler, options: CompilerOptions) -> Result<Self> {
         let compiler_flags = match project_compiler {
             ProjectCompiler::GCC => gcc::FLAGS,
-            _ => unimplemented!()
+            ProjectCompiler::GPP => gpp::FLAGS,
+            ProjectCompiler::CLANG => clang::FLAGS,
+            ProjectCompiler::MSVC => return Err(Error::UnsupportedCompiler(project_compiler.into()).into()),
         };
+        
+        if !util::check_if_available(&project_compiler, &options.target_language) {
+            return Err(Error::CompilerNotFound(project_compiler.into()).into());
+        }
 
-        Compiler {
+        let profile = &options.profile;
+        
+        let mut compile_args = Vec::new();
+        if let Some(level) = &profile.optimization {
+            compile_args.push(format!("{}{}", compiler_flags.optimization, level));
+        }
+        if profile.debug_info.unwrap_or(false) {
+            compile_args.push(compiler_flags.debug_info.to_string());
+        }
+        compile_args.extend(split_args(&profile.additional_compiler_args));
+        
+        let mut link_args = split_args(&profile.additional_linker_args);
+        link_args.extend(profile.linker_args.iter().flatten().cloned());
+        
+        let toolchain = [
+            project_compiler.executable_name(),
+            project_compiler.linker_name(&ProjectLanguage::C),
+            project_compiler.linker_name(&ProjectLanguage::CPP),
+            project_compiler.archiver_name(),
+        ].into_iter()
+            .map(|program| {
+                let identity = util::identify_program(&program);
+                (program, identity)
+            })
+            .collect();
+        let signature_directory = options.root_path
+            .join(PROJECT_DIRECTORY_NAME)
+            .join(signature::SIGNATURE_DIRECTORY_NAME);
+
+        Ok(Compiler {
             command: CompilerCommand::new(
                 project_compiler.executable_name(),
+                project_compiler.linker_name(&ProjectLanguage::C),
+                project_compiler.linker_name(&ProjectLanguage::CPP),
+                project_compiler.archiver_name(),
                 compiler_flags,
                 options.root_path,
-                options.include_paths.unwrap_or(Vec::new()),
-                options.additional_flags.map_or(Vec::new(), |flags| flags.split_whitespace().map(String::from).collect()), 
+                options.include_paths.unwrap_or_default(),
+                split_args(&options.additional_flags),
+                compile_args,
+                link_args,
             ),
-            compiler: project_compiler,
-            language: options.target_language,
+            jobs: options.jobs,
+            message_format: options.message_format,
+            keep_going: options.keep_going,
+            dry_run: options.dry_run,
+            signatures: SignatureStore::new(signature_directory, toolchain),
+        })
+    }
+    
+    /// Compiles the target's source files and links (or archives) them into the target's output
+    /// file
+    pub fn build(&self, target: TargetInformation) -> Result<()> {
+        let result = self.build_target(&target);
+        
+        if self.message_format == MessageFormat::Json {
+            Message::UnitFinished {
+                unit: &target.name,
+                success: result.is_ok(),
+                fresh: matches!(result, Ok(true)),
+                output: &target.output_file,
+            }.emit();
+        }
+        
+        result.map(|_| ())
+    }
+    
+    /// Links the built target's objects once more into the destination file with the runtime
+    /// search path of the installed target rather than the one of the build tree. Returns `false`
+    /// without linking anything if the installed target doesn't need a different search path, so
+    /// its output file can be copied as it is
+    pub fn link_for_install(&self, target: &TargetInformation, destination: &Path) -> Result<bool> {
+        if target.installed_runtime_library_directories.is_empty()
+            || !matches!(target.r#type, UnitType::Binary | UnitType::DynamicLibrary)
+        {
+            return Ok(false);
         }
+        
+        let object_paths = self.get_object_files(target);
+        let command_executor = self.prepare_link_command(
+            target,
+            &object_paths,
+            destination,
+            &target.installed_runtime_library_directories,
+        ).with_context(|| format!("linking {}", destination.display()))?;
+        
+        self.report(Verbosity::Normal, format!("Relinking {} for installation", target.name));
+        self.link_objects(destination, command_executor)
+            .with_context(|| format!("linking {}", destination.display()))?;
+        
+        Ok(true)
     }
     
-    pub fn build(&self, target: TargetInformation) {
-        let object_paths = self.compile(&target);
+    /// Builds the target. Returns whether the target's output file was already up to date
+    fn build_target(&self, target: &TargetInformation) -> Result<bool> {
+        let (object_paths, objects_changed) = self.compile(target)?;
+        
+        let command_executor = match target.r#type {
+            UnitType::StaticLibrary => self.prepare_archive_command(target, &object_paths),
+            _ => self.prepare_link_command(target, &object_paths, &target.output_file, &target.runtime_library_directories),
+        }.with_context(|| format!("linking {}", target.output_file.display()))?;
+        let signature = self.signatures.signature(&command_executor);
         
-        if let Err(err) = object_paths {
-            eprintln!("Compilation failed for target '{}'", &target.name);
-            eprintln!("{}", err);
-            process::exit(1);
+        let library_files = target.external_libraries.iter()
+            .filter_map(|library| match library {
+                LinkLibrary::File(file) => Some(file),
+                LinkLibrary::Name(_) => None,
+            });
+        let link_inputs: Vec<PathBuf> = object_paths.iter()
+            .chain(&target.link_libraries)
+            .chain(library_files)
+            .cloned()
+            .collect();
+        if !objects_changed
+            && dependency::is_newer_than_all(&target.output_file, &link_inputs)
+            && self.signatures.matches(&target.output_file, &signature)
+        {
+            self.report(Verbosity::Verbose, format!("Target '{}' is up to date", target.name));
+            return Ok(true);
         }
         
-        if let Err(err) = self.link_objects(&target, object_paths.unwrap()) {
-            eprintln!("Linking failed for target '{}'", &target.name);
-            eprintln!("{}", err);
-            process::exit(1);
+        let action = match target.r#type {
+            UnitType::StaticLibrary => "Archiving",
+            _ => "Linking",
+        };
+        self.report(Verbosity::Normal, format!("{} {}", action, target.name));
+        
+        match target.r#type {
+            UnitType::Binary | UnitType::Test => self.link_objects(&target.output_file, command_executor),
+            UnitType::StaticLibrary => self.archive_objects(target, command_executor),
+            UnitType::DynamicLibrary => self.link_objects(&target.output_file, command_executor)
+                .and_then(|_| self.create_library_symlinks(target)),
+        }.with_context(|| format!("linking {}", target.output_file.display()))?;
+        
+        if !self.dry_run {
+            self.signatures.store(&target.output_file, &signature)
+                .with_context(|| format!("storing the signature of {}", target.output_file.display()))?;
         }
         
-        println!("Build finished successfully for target '{}'", target.name);
+        self.report(Verbosity::Verbose, format!("Build finished successfully for target '{}'", target.name));
+        Ok(false)
+    }
+    
+    /// Prints the human-readable progress message if the output is at least as verbose as the
+    /// level (unless machine-readable messages are used)
+    fn report(&self, level: Verbosity, message: impl Display) {
+        if self.message_format == MessageFormat::Human && output::verbosity() >= level {
+            println!("{}", message);
+        }
+    }
+    
+    /// Returns the description of the command which is printed according to the verbosity
+    fn describe_command(&self, command_executor: &CompilerCommandExecutor) -> String {
+        match output::verbosity() {
+            Verbosity::VeryVerbose => command_executor.describe(),
+            _ => command_executor.command_line(),
+        }
+    }
+    
+    /// Prints the described command if the output is verbose. During the dry run the commands are
+    /// always printed, since they are its only output
+    fn report_command(&self, command: &str) {
+        if self.dry_run {
+            println!("{}", command);
+        } else {
+            self.report(Verbosity::Verbose, format!("Executing: {}", command));
+        }
     }
 
-    /// Compiles target's source files into object files with the same name
-    fn compile(&self, target: &TargetInformation) -> Result<Vec<PathBuf>> {
-        let mut object_files = Vec::new();
+    /// Compiles target's source files into object files. Source files whose object file is newer
+    /// than the source and every header it includes are not recompiled. Outdated sources are
+    /// compiled concurrently by up to `jobs` worker threads, while their results are reported in
+    /// the order of the source files. No new sources are scheduled after the first failure, unless
+    /// the compiler keeps going, in which case all the failures are reported together.
+    /// Returns the paths of all the target's object files and whether any of them was rebuilt
+    fn compile(&self, target: &TargetInformation) -> Result<(Vec<PathBuf>, bool)> {
+        let object_files = self.get_object_files(target);
         
-        for source_file in &target.source_files {
-            let mut command_executor = self.command.executor()?;
+        // Sources are recompiled if they (or their headers) have changed, or if their compile
+        // command or the toolchain has changed
+        let mut outdated: Vec<(&PathBuf, &ProjectLanguage, &PathBuf)> = Vec::new();
+        for ((source_file, language), object_file) in target.source_files.iter().zip(&object_files) {
+            let command_executor = self.prepare_compile_command(target, source_file, language, object_file)?;
             
-            command_executor.set_language(&self.language);
-            command_executor.set_compile_flag();
-
-            target.include_paths.iter().try_for_each(|p| command_executor.add_include_path(p))?;
-            target.additional_args.iter().for_each(|arg| command_executor.add_arg(arg));
-            
-            let object_file = {
-                let mut file = target.intermediate_directory.join(&source_file.file_name().unwrap());
-                file.set_extension("o");
-                file
-            };
-            
-            command_executor.set_output_file(&object_file)?;
-            command_executor.add_input_file(&source_file)?;
+            if !dependency::is_up_to_date(object_file, source_file, &object_file.with_extension("d"))
+                || !self.signatures.matches(object_file, &self.signatures.signature(&command_executor))
+            {
+                outdated.push((source_file, language, object_file));
+            }
+        }
+        
+        if outdated.is_empty() {
+            return Ok((object_files, false));
+        }
+        
+        self.report(Verbosity::Normal, format!(
+            "Compiling {} ({} {})",
+            target.name, outdated.len(), if outdated.len() == 1 { "file" } else { "files" },
+        ));
+        
+        let next_job = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel();
+        
+        let mut results: Vec<Option<Result<(String, String)>>> = outdated.iter().map(|_| None).collect();
+        
+        thread::scope(|scope| {
+            for _ in 0..self.jobs.min(outdated.len()) {
+                let sender = sender.clone();
+                let (next_job, failed, outdated) = (&next_job, &failed, &outdated);
+                
+                scope.spawn(move || loop {
+                    if failed.load(Ordering::SeqCst) && !self.keep_going {
+                        break;
+                    }
+                    
+                    let index = next_job.fetch_add(1, Ordering::SeqCst);
+                    let Some((source_file, language, object_file)) = outdated.get(index) else {
+                        break;
+                    };
+                    
+                    let result = self.compile_source(target, source_file, language, object_file);
+                    if result.is_err() {
+                        failed.store(true, Ordering::SeqCst);
+                    }
+                    
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
             
-            let output = command_executor.execute()?;
-
-            if !output.status.success() {
-                return Err(Error::CompileError(output));
+            // Results are printed as soon as every result before them has arrived, so the output
+            // doesn't depend on which worker finishes first
+            let mut next_to_report = 0;
+            for (index, result) in receiver {
+                results[index] = Some(result);
+                
+                while let Some(Some(result)) = results.get(next_to_report) {
+                    match result {
+                        Ok((description, warnings)) => {
+                            self.report_command(description);
+                            eprint!("{}", warnings);
+                        },
+                        // Otherwise the only failure is reported by the caller
+                        Err(err) if self.keep_going => eprintln!("error: {}", err),
+                        Err(_) => {},
+                    }
+                    next_to_report += 1;
+                }
+            }
+        });
+        
+        let mut failures = outdated.iter()
+            .zip(results)
+            .filter_map(|((source_file, _, _), result)| match result {
+                Some(Err(err)) => Some((*source_file, err)),
+                _ => None,
+            });
+        
+        if self.keep_going {
+            let failures: Vec<(PathBuf, String)> = failures
+                .map(|(source_file, err)| (source_file.clone(), err.first_line()))
+                .collect();
+            if !failures.is_empty() {
+                return Err(Error::SourcesFailed(failures).into());
+            }
+        } else if let Some((_, err)) = failures.next() {
+            return Err(err);
+        }
+        
+        Ok((object_files, true))
+    }
+    
+    /// Returns compilation database entries for all the target's source files without compiling
+    /// them
+    pub fn get_compile_commands(&self, target: &TargetInformation) -> Result<Vec<CompileCommand>> {
+        let directory = env::current_dir()?;
+        
+        target.source_files.iter()
+            .zip(self.get_object_files(target))
+            .map(|((source_file, language), object_file)| {
+                let command_executor = self.prepare_compile_command(target, source_file, language, &object_file)?;
+                
+                Ok(CompileCommand {
+                    directory: directory.clone(),
+                    file: source_file.clone(),
+                    arguments: command_executor.arguments(),
+                    output: object_file,
+                })
+            })
+            .collect()
+    }
+    
+    /// Returns the paths of the target's object files, which mirror the location of the source
+    /// files in the target's intermediate directory (`src/app/util.c` -> `<dir>/util.c.o`)
+    fn get_object_files(&self, target: &TargetInformation) -> Vec<PathBuf> {
+        target.source_files.iter()
+            .map(|(source_file, _)| {
+                let relative_path = source_file.strip_prefix(&target.source_directory)
+                    .unwrap_or(Path::new(source_file.file_name().unwrap()));
+                
+                let mut file = target.intermediate_directory.join(relative_path).into_os_string();
+                file.push(".o");
+                PathBuf::from(file)
+            })
+            .collect()
+    }
+    
+    /// Compiles a single source file into the object file, generating its dependency file
+    /// alongside. Returns the description of the executed command and the warnings reported by
+    /// the compiler, unless they were already emitted as machine-readable messages
+    fn compile_source(&self, target: &TargetInformation, source_file: &Path, language: &ProjectLanguage, object_file: &Path) -> Result<(String, String)> {
+        let command_executor = self.prepare_compile_command(target, source_file, language, object_file)?;
+        
+        let command_line = command_executor.command_line();
+        let description = self.describe_command(&command_executor);
+        if self.dry_run {
+            return Ok((description, String::new()));
+        }
+        let signature = self.signatures.signature(&command_executor);
+        
+        create_output_directory(object_file)?;
+        let output = command_executor.execute()
+            .with_context(|| format!("compiling {}", source_file.display()))?;
+        
+        let mut warnings = String::from_utf8_lossy(&output.stderr).to_string();
+        if self.message_format == MessageFormat::Json {
+            for diagnostic in Diagnostic::parse(&warnings) {
+                Message::CompilerMessage {
+                    unit: &target.name,
+                    source: source_file,
+                    diagnostic: &diagnostic,
+                }.emit();
             }
+            warnings.clear();
+        }
 
-            object_files.push(object_file);
+        if !output.status.success() {
+            return Err(Error::CompileError(command_line, output))
+                .with_context(|| format!("compiling {}", source_file.display()));
         }
+        
+        self.signatures.store(object_file, &signature)
+            .with_context(|| format!("storing the signature of {}", object_file.display()))?;
 
-        Ok(object_files)
+        Ok((description, warnings))
     }
-
-    /// Links compiled object files to the output file
-    fn link_objects(&self, target: &TargetInformation, object_files: Vec<PathBuf>) -> Result<()> {
+    
+    /// Prepares the command which compiles the source file of the language into the object file
+    fn prepare_compile_command(&self, target: &TargetInformation, source_file: &Path, language: &ProjectLanguage, object_file: &Path) -> Result<CompilerCommandExecutor<'_>> {
         let mut command_executor = self.command.executor()?;
         
-        object_files.iter().try_for_each(|file| command_executor.add_input_file(file))?;
+        command_executor.set_language(language);
+        command_executor.set_compile_flag();
+        if target.position_independent {
+            command_executor.set_position_independent();
+        }
+        command_executor.set_dependency_file(&object_file.with_extension("d"));
+        if self.message_format == MessageFormat::Json {
+            command_executor.set_parseable_fixits();
+        }
+
+        target.include_paths.iter().try_for_each(|p| command_executor.add_include_path(p))?;
+        target.defines.iter().for_each(|define| command_executor.add_define(define));
+        target.additional_args.iter().for_each(|arg| command_executor.add_arg(arg));
+        
+        command_executor.set_output_file(object_file);
+        command_executor.add_input_file(source_file)?;
+        
+        Ok(command_executor)
+    }
+
+    /// Prepares the command which links compiled object files to the output file. Dynamic
+    /// libraries are linked as shared objects with their soname, and the target gets the runtime
+    /// search path for the project's dynamic libraries. The objects are followed by the linker
+    /// arguments, the library search paths, the libraries of the project's units and then the
+    /// other libraries
+    fn prepare_link_command(
+        &self,
+        target: &TargetInformation,
+        object_files: &[PathBuf],
+        output_file: &Path,
+        runtime_library_directories: &[PathBuf],
+    ) -> Result<CompilerCommandExecutor<'_>> {
+        let mut command_executor = self.command.linker(&target.link_language);
+        
+        if let UnitType::DynamicLibrary = target.r#type {
+            command_executor.set_shared();
+            if let Some(soname) = &target.soname {
+                command_executor.set_soname(soname);
+            }
+        }
+        
+        object_files.iter().try_for_each(|file| self.add_link_input(&mut command_executor, file))?;
+        self.command.link_args().iter()
+            .chain(&target.linker_args)
+            .for_each(|arg| command_executor.add_arg(arg));
+        target.library_paths.iter().try_for_each(|dir| command_executor.add_library_path(dir))?;
+        for file in &target.link_libraries {
+            if target.whole_archives.contains(file) {
+                command_executor.add_whole_archive(file);
+            } else {
+                self.add_link_input(&mut command_executor, file)?;
+            }
+        }
+        for library in &target.external_libraries {
+            match library {
+                LinkLibrary::Name(name) => command_executor.add_link_library(name),
+                LinkLibrary::File(file) => self.add_link_input(&mut command_executor, file)?,
+            }
+        }
+        runtime_library_directories.iter().for_each(|dir| command_executor.add_runtime_library_path(dir));
+        
+        command_executor.set_output_file(output_file);
         
-        let output_file = target.output_directory.join(&target.name);
-        command_executor.set_output_file(&output_file)?;
+        Ok(command_executor)
+    }
+    
+    /// Prepares the command which archives compiled object files into a static library
+    fn prepare_archive_command(&self, target: &TargetInformation, object_files: &[PathBuf]) -> Result<CompilerCommandExecutor<'_>> {
+        let mut command_executor = self.command.archiver();
+        
+        command_executor.set_archive_file(&target.output_file);
+        object_files.iter().try_for_each(|file| self.add_link_input(&mut command_executor, file))?;
+        
+        Ok(command_executor)
+    }
+    
+    /// Adds the object file or library to the link (or archive) command. During the dry run the
+    /// inputs produced by the previous steps don't exist, so their existence is not verified
+    fn add_link_input(&self, command_executor: &mut CompilerCommandExecutor, file: &Path) -> std::io::Result<()> {
+        if self.dry_run {
+            command_executor.add_arg(&file.to_string_lossy());
+            Ok(())
+        } else {
+            command_executor.add_input_file(file)
+        }
+    }
 
+    /// Executes the prepared link command
+    fn link_objects(&self, output_file: &Path, command_executor: CompilerCommandExecutor) -> Result<()> {
+        let command_line = command_executor.command_line();
+        self.report_command(&self.describe_command(&command_executor));
+        if self.dry_run {
+            return Ok(());
+        }
+        
+        create_output_directory(output_file)?;
+        let output = command_executor.execute()?;
+        
+        if !output.status.success() {
+            return Err(Error::LinkError(command_line, output).into());
+        }
+
+        Ok(())
+    }
+    
+    /// Creates the soname and linker name symbolic links of the dynamic library, replacing the
+    /// existing ones
+    fn create_library_symlinks(&self, target: &TargetInformation) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+        
+        for (link, original) in &target.library_symlinks {
+            if link.symlink_metadata().is_ok() {
+                fs::remove_file(link)?;
+            }
+            file::create_symlink(original, link)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Executes the prepared archive command. The existing library is replaced so that objects of
+    /// removed source files don't remain in it
+    fn archive_objects(&self, target: &TargetInformation, command_executor: CompilerCommandExecutor) -> Result<()> {
+        let command_line = command_executor.command_line();
+        self.report_command(&self.describe_command(&command_executor));
+        if self.dry_run {
+            return Ok(());
+        }
+        
+        if target.output_file.exists() {
+            fs::remove_file(&target.output_file)?;
+        }
+        create_output_directory(&target.output_file)?;
         let output = command_executor.execute()?;
         
         if !output.status.success() {
-            return Err(Error::LinkError(output));
+            return Err(Error::ArchiveError(command_line, output).into());
         }
 
         Ok(())
@@ -115,6 +558,15 @@ impl Compiler {
     
 }
 
+/// Makes sure the directory the output file is going to be written to exists. Directories are
+/// created only right before the commands are executed, so the dry run doesn't create any
+fn create_output_directory(output_file: &Path) -> std::io::Result<()> {
+    match output_file.parent() {
+        Some(directory) => fs::create_dir_all(directory),
+        None => Ok(()),
+    }
+}
+
 /// Options for configuring compiler's behaviour and supplying persistent attributes for the whole
 /// duration of the process
 pub struct CompilerOptions {
@@ -127,6 +579,17 @@ pub struct CompilerOptions {
     include_paths: Option<Vec<PathBuf>>,
     /// Additional flags which are going to be supplied to the compiler
     additional_flags: Option<String>,
+    /// Build profile which specifies the optimization level, debug information and additional
+    /// profile-specific arguments
+    profile: ProfileConfig,
+    /// Maximum number of compiler processes running at the same time
+    jobs: usize,
+    /// Format of the messages reported during the build
+    message_format: MessageFormat,
+    /// Whether to keep compiling the remaining source files after a failure
+    keep_going: bool,
+    /// Whether to only print the commands instead of executing them
+    dry_run: bool,
 }
 
 impl CompilerOptions {
@@ -135,14 +598,40 @@ impl CompilerOptions {
         target_language: ProjectLanguage,
         include_paths: Option<Vec<PathBuf>>,
         additional_flags: Option<String>,
+        profile: ProfileConfig,
     ) -> Self {
         CompilerOptions {
             root_path,
             target_language,
             include_paths,
             additional_flags,
+            profile,
+            jobs: util::available_jobs(),
+            message_format: MessageFormat::default(),
+            keep_going: false,
+            dry_run: false,
         }
     }
+    
+    /// Sets the maximum number of compiler processes running at the same time
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs;
+    }
+    
+    /// Sets the format of the messages reported during the build
+    pub fn set_message_format(&mut self, message_format: MessageFormat) {
+        self.message_format = message_format;
+    }
+    
+    /// Sets whether to keep compiling the remaining source files after a failure
+    pub fn set_keep_going(&mut self, keep_going: bool) {
+        self.keep_going = keep_going;
+    }
+    
+    /// Sets whether to only print the commands instead of executing them
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
 }
 
 /// Required information about the target which is used to perform target-specific actions
@@ -151,36 +640,149 @@ pub struct TargetInformation {
     name: String,
     /// Type of target
     r#type: UnitType,
-    /// Target's source files
-    source_files: Vec<PathBuf>,
-    /// The location in which target will be output
-    output_directory: PathBuf,
-    /// The location for holding target's intermediate object
+    /// Directory containing target's source files
+    source_directory: PathBuf,
+    /// Target's source files together with the language each of them is compiled as
+    source_files: Vec<(PathBuf, ProjectLanguage)>,
+    /// The final output file of the target (binary or library)
+    output_file: PathBuf,
+    /// The location for holding target's intermediate object files (specific to the target)
     intermediate_directory: PathBuf,
     /// Target-specific include paths
     include_paths: Vec<PathBuf>,
+    /// Target-specific preprocessor definitions (`NAME` or `NAME=VALUE`)
+    defines: Vec<String>,
     /// Target-specific additional flags
     additional_args: Vec<String>,
+    /// Library files of the target's dependencies which are linked into the target
+    link_libraries: Vec<PathBuf>,
+    /// Static libraries among the linked library files which are linked as a whole
+    whole_archives: Vec<PathBuf>,
+    /// Additional library search paths (relative to the project root)
+    library_paths: Vec<PathBuf>,
+    /// Libraries other than the project's units which are linked into the target
+    external_libraries: Vec<LinkLibrary>,
+    /// Target-specific arguments passed only to the linker
+    linker_args: Vec<String>,
+    /// Language which determines the linker driver (C++ if any of the linked objects are C++)
+    link_language: ProjectLanguage,
+    /// Whether the target's objects have to be compiled as position-independent code
+    position_independent: bool,
+    /// Soname of the dynamic library target
+    soname: Option<String>,
+    /// Symbolic links to create alongside the dynamic library target (link path and the file it
+    /// points to)
+    library_symlinks: Vec<(PathBuf, PathBuf)>,
+    /// Directories (relative to the target output) to search for dynamic libraries at runtime
+    runtime_library_directories: Vec<PathBuf>,
+    /// Directories (relative to the installed target) to search for dynamic libraries at runtime
+    /// once the target is installed
+    installed_runtime_library_directories: Vec<PathBuf>,
 }
 
 impl TargetInformation {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         r#type: UnitType,
-        source_files: Vec<PathBuf>,
-        output_directory: PathBuf,
+        source_directory: PathBuf,
+        source_files: Vec<(PathBuf, ProjectLanguage)>,
+        output_file: PathBuf,
         intermediate_directory: PathBuf,
         include_paths: Option<Vec<PathBuf>>,
-        additional_args: Option<String>,
+        defines: Vec<String>,
+        additional_args: Vec<String>,
+        link_libraries: Vec<PathBuf>,
+        whole_archives: Vec<PathBuf>,
+        library_paths: Vec<PathBuf>,
+        external_libraries: Vec<LinkLibrary>,
+        linker_args: Vec<String>,
+        link_language: ProjectLanguage,
+        position_independent: bool,
+        soname: Option<String>,
+        library_symlinks: Vec<(PathBuf, PathBuf)>,
+        runtime_library_directories: Vec<PathBuf>,
+        installed_runtime_library_directories: Vec<PathBuf>,
     ) -> Self {
         TargetInformation {
             name,
             r#type,
+            source_directory,
             source_files,
-            output_directory,
+            output_file,
             intermediate_directory,
-            include_paths: include_paths.unwrap_or(Vec::new()),
-            additional_args: additional_args.map_or(Vec::new(), |a| a.split_whitespace().map(String::from).collect()),
+            include_paths: include_paths.unwrap_or_default(),
+            defines,
+            additional_args,
+            link_libraries,
+            whole_archives,
+            library_paths,
+            external_libraries,
+            linker_args,
+            link_language,
+            position_independent,
+            soname,
+            library_symlinks,
+            runtime_library_directories,
+            installed_runtime_library_directories,
         }
     }
 }
+
+/// Splits the string of additional arguments into separate arguments
+fn split_args(args: &Option<String>) -> Vec<String> {
+    args.as_ref().map_or(Vec::new(), |args| args.split_whitespace().map(String::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+    use crate::config::ProjectConfig;
+
+    #[test]
+    fn unit_defines_override_public_profile_and_project_ones() {
+        let directory = env::temp_dir().join(format!("copper-defines-{}", process::id()));
+        fs::create_dir_all(directory.join("src/app")).unwrap();
+        fs::create_dir_all(directory.join("src/lib")).unwrap();
+        fs::write(directory.join("src/app/main.c"), "int main(void) { return 0; }\n").unwrap();
+        fs::write(directory.join("src/lib/lib.c"), "int lib(void) { return 0; }\n").unwrap();
+        fs::write(directory.join(crate::config::PROJECT_FILE_NAME), r#"
+            name = "project"
+            language = "c"
+            compiler = "gcc"
+            defines = { LEVEL = 1, ORIGIN = "project", PROJECT_ONLY = true }
+
+            [profile.release]
+            defines = { LEVEL = 2, ORIGIN = "profile" }
+
+            [[Unit]]
+            name = "app"
+            type = "binary"
+            source = "src/app"
+            dependencies = ["lib"]
+            public-defines = { ORIGIN = "app" }
+            defines = { LEVEL = 4, NDEBUG = false, MESSAGE = 'say "hi" there' }
+
+            [[Unit]]
+            name = "lib"
+            type = "static-library"
+            source = "src/lib"
+            public-defines = { LEVEL = 3, ORIGIN = "lib", LIB_ONLY = 1 }
+        "#).unwrap();
+
+        let project = ProjectConfig::import(&directory).unwrap();
+        let target = project.find_unit("app").unwrap()
+            .get_target_information(&project, ProfileConfig::RELEASE);
+        fs::remove_dir_all(&directory).unwrap();
+
+        // The unit's `NDEBUG = false` removes the definition inherited from the release profile
+        assert_eq!(target.unwrap().unwrap().defines, [
+            "LEVEL=4",
+            "LIB_ONLY=1",
+            r#"MESSAGE=say "hi" there"#,
+            "ORIGIN=app",
+            "PROJECT_ONLY",
+        ]);
+    }
+}
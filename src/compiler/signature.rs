@@ -0,0 +1,126 @@
+//! Command signatures, which are used to rebuild the outputs whose commands or toolchain have
+//! changed since they were produced
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io, path};
+use super::command::CompilerCommandExecutor;
+
+/// Name of the directory (within the project's Copper directory) holding the signatures
+pub const SIGNATURE_DIRECTORY_NAME: &str = "signatures";
+
+/// Persistent storage of the signatures of the commands which produced the output files. Every
+/// signature is kept in a separate file named after the hash of the output file's path, so the
+/// signatures can be written by multiple compiler jobs at the same time
+pub struct SignatureStore {
+    directory: PathBuf,
+    /// Identity (location and version) of every program of the toolchain
+    toolchain: HashMap<String, String>,
+}
+
+impl SignatureStore {
+    pub fn new(directory: PathBuf, toolchain: HashMap<String, String>) -> Self {
+        SignatureStore {
+            directory,
+            toolchain,
+        }
+    }
+    
+    /// Returns the signature of the command: the hash of its program's location and version and
+    /// all of its arguments affecting the output (so e.g. switching the message format doesn't
+    /// rebuild anything)
+    pub fn signature(&self, command_executor: &CompilerCommandExecutor) -> String {
+        let arguments = command_executor.output_arguments();
+        let identity = arguments.first()
+            .and_then(|program| self.toolchain.get(program))
+            .map(String::as_str)
+            .unwrap_or_default();
+        
+        let hash = arguments.iter()
+            .fold(fnv1a(FNV_OFFSET_BASIS, identity.as_bytes()), |hash, argument| {
+                // Separator makes sure that differently split arguments don't produce the same hash
+                fnv1a(fnv1a(hash, &[0]), argument.as_bytes())
+            });
+        format!("{:016x}", hash)
+    }
+    
+    /// Returns whether the signature stored for the output file matches the signature
+    pub fn matches(&self, output_file: &Path, signature: &str) -> bool {
+        fs::read_to_string(self.signature_file(output_file))
+            .is_ok_and(|stored| stored.trim() == signature)
+    }
+    
+    /// Stores the signature of the command which produced the output file
+    pub fn store(&self, output_file: &Path, signature: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.signature_file(output_file), signature)
+    }
+    
+    /// Returns the location of the output file's signature. The output file's absolute path is
+    /// used, so the signature doesn't depend on how the output path is spelled
+    fn signature_file(&self, output_file: &Path) -> PathBuf {
+        let output_file = path::absolute(output_file).unwrap_or(output_file.to_path_buf());
+        let hash = fnv1a(FNV_OFFSET_BASIS, output_file.to_string_lossy().as_bytes());
+        self.directory.join(format!("{:016x}", hash))
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Continues the 64-bit FNV-1a hash with the bytes. Unlike the standard library's hasher, the
+/// result is stable between program versions, so it can be persisted
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+    use crate::compiler::gcc;
+
+    fn toolchain(version: &str) -> HashMap<String, String> {
+        HashMap::from([("gcc".to_string(), format!("/usr/bin/gcc {}", version))])
+    }
+
+    fn signature(store: &SignatureStore, arguments: &[&str], parseable_fixits: bool) -> String {
+        let mut command_executor = CompilerCommandExecutor::new("gcc", &gcc::FLAGS, Path::new("."));
+        arguments.iter().for_each(|arg| command_executor.add_arg(arg));
+        if parseable_fixits {
+            command_executor.set_parseable_fixits();
+        }
+        store.signature(&command_executor)
+    }
+
+    #[test]
+    fn changes_with_arguments_and_toolchain_only() {
+        let store = SignatureStore::new(PathBuf::new(), toolchain("13.2.0"));
+        let original = signature(&store, &["-O2", "-DNAME=a b"], false);
+
+        assert_eq!(signature(&store, &["-O2", "-DNAME=a b"], true), original);
+        assert_ne!(signature(&store, &["-O3", "-DNAME=a b"], false), original);
+        assert_ne!(signature(&store, &["-O2", "-DNAME=a", "b"], false), original);
+        assert_ne!(signature(&SignatureStore::new(PathBuf::new(), toolchain("14.1.0")), &["-O2", "-DNAME=a b"], false), original);
+    }
+
+    #[test]
+    fn stored_signature_matches_only_its_output_file() {
+        let directory = env::temp_dir().join(format!("copper-signature-{}", process::id()));
+        let store = SignatureStore::new(directory.clone(), toolchain("13.2.0"));
+        let output_file = directory.join("app");
+        let signature = signature(&store, &["-O2"], false);
+
+        let matches_before_store = store.matches(&output_file, &signature);
+        store.store(&output_file, &signature).unwrap();
+        let matches = store.matches(&output_file, &signature);
+        let matches_other_signature = store.matches(&output_file, "0000000000000000");
+        let matches_other_file = store.matches(&directory.join("lib"), &signature);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(!matches_before_store);
+        assert!(matches);
+        assert!(!matches_other_signature);
+        assert!(!matches_other_file);
+    }
+}
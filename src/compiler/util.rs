@@ -1,26 +1,28 @@
 //! Utility module containing miscellaneous functions related to compiler
 
-use std::env;
+use std::{env, thread};
+use std::process::Command;
 use std::path::PathBuf;
-use crate::config::ProjectCompiler;
+use crate::config::{ProjectCompiler, ProjectLanguage};
 
 // TODO: improve logic, add better availability feedback
 /// A basic function to check weather the compiler is available to use (checks if the system has the
-/// compiler executable in $PATH).
-pub fn check_if_available(compiler: &ProjectCompiler) -> bool {
+/// compiler executable and the linker driver for the language in $PATH).
+pub fn check_if_available(compiler: &ProjectCompiler, language: &ProjectLanguage) -> bool {
     match compiler {
-        ProjectCompiler::GCC => find_executable(compiler.executable_name()).is_some(),
-        ProjectCompiler::GPP => find_executable(compiler.executable_name()).is_some(),
-        ProjectCompiler::CLANG => find_executable(compiler.executable_name()).is_some(),
+        ProjectCompiler::GCC | ProjectCompiler::GPP | ProjectCompiler::CLANG => {
+            find_executable(compiler.executable_name()).is_some()
+                && find_executable(compiler.linker_name(language)).is_some()
+        },
         ProjectCompiler::MSVC => {
             if !cfg!(windows) { return false; }
 
-            if let None = find_executable(compiler.executable_name()) {
+            if find_executable(compiler.executable_name()).is_none() {
                 return false;
             }
 
             // Additional program for linking (used to separately link DLLs and such)
-            if let None = find_executable("link".to_string()) {
+            if find_executable("link".to_string()).is_none() {
                 return false;
             }
 
@@ -29,6 +31,26 @@ pub fn check_if_available(compiler: &ProjectCompiler) -> bool {
     }
 }
 
+/// Returns the default number of parallel compiler jobs, which is the amount of available CPUs
+pub fn available_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Returns the identity of the toolchain program: its location and the first line of its version
+/// output. Programs which are not found or don't report their version are identified by their
+/// name only
+pub fn identify_program(executable_name: &str) -> String {
+    let location = find_executable(executable_name.to_string()).unwrap_or_else(|| PathBuf::from(executable_name));
+    
+    let version = Command::new(&location)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    
+    format!("{}\n{}", location.display(), version)
+}
+
 /// Searches the system's $PATH environment variable for the matching executable name
 fn find_executable(executable_name: String) -> Option<PathBuf> {
     let mut executable_name = PathBuf::from(executable_name);
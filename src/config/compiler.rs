@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
-use crate::error::Error;
+use super::{Error, ProjectLanguage};
 
 /// Enum representing available project compilers
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(try_from = "String", into = "String")]
 pub enum ProjectCompiler {
     GCC,
@@ -29,6 +30,26 @@ impl ProjectCompiler {
             ProjectCompiler::MSVC => "cl".to_string()
         }
     }
+    
+    /// Returns the name of the compiler driver used to link objects of the specified language.
+    /// C++ objects have to be linked by the C++ driver in order to link the C++ standard library
+    pub fn linker_name(&self, language: &ProjectLanguage) -> String {
+        match (self, language) {
+            (ProjectCompiler::GCC | ProjectCompiler::GPP, ProjectLanguage::C) => "gcc".to_string(),
+            (ProjectCompiler::GCC | ProjectCompiler::GPP, ProjectLanguage::CPP) => "g++".to_string(),
+            (ProjectCompiler::CLANG, ProjectLanguage::C) => "clang".to_string(),
+            (ProjectCompiler::CLANG, ProjectLanguage::CPP) => "clang++".to_string(),
+            (ProjectCompiler::MSVC, _) => "link".to_string(),
+        }
+    }
+    
+    /// Returns the name of the program used to archive object files into static libraries
+    pub fn archiver_name(&self) -> String {
+        match self {
+            ProjectCompiler::GCC | ProjectCompiler::GPP | ProjectCompiler::CLANG => "ar".to_string(),
+            ProjectCompiler::MSVC => "lib".to_string()
+        }
+    }
 }
 
 impl TryFrom<String> for ProjectCompiler {
@@ -40,18 +61,18 @@ impl TryFrom<String> for ProjectCompiler {
             Self::GPP_STR | "gpp" => Ok(ProjectCompiler::GPP),
             Self::CLANG_STR => Ok(ProjectCompiler::CLANG),
             Self::MSVC_STR | "cl" => Ok(ProjectCompiler::MSVC),
-            _ => Err(Error::EnumParseError(format!("Unexpected compiler value: {}", value)))
+            _ => Err(Error::InvalidCompiler(value))
         }
     }
 }
 
-impl Into<String> for ProjectCompiler {
-    fn into(self) -> String {
-        match self {
-            ProjectCompiler::GCC => Self::GCC_STR.to_string(),
-            ProjectCompiler::GPP => Self::GPP_STR.to_string(),
-            ProjectCompiler::CLANG => Self::CLANG_STR.to_string(),
-            ProjectCompiler::MSVC => Self::MSVC_STR.to_string()
+impl From<ProjectCompiler> for String {
+    fn from(value: ProjectCompiler) -> Self {
+        match value {
+            ProjectCompiler::GCC => ProjectCompiler::GCC_STR.to_string(),
+            ProjectCompiler::GPP => ProjectCompiler::GPP_STR.to_string(),
+            ProjectCompiler::CLANG => ProjectCompiler::CLANG_STR.to_string(),
+            ProjectCompiler::MSVC => ProjectCompiler::MSVC_STR.to_string()
         }
     }
 }
\ No newline at end of file
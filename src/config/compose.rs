@@ -0,0 +1,189 @@
+//! Composition of the project file with the shared files it extends and the fragments it includes
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use crate::error::{Context, Result};
+use super::Error;
+
+const EXTENDS_KEY: &str = "extends";
+const INCLUDE_KEY: &str = "include";
+pub const UNIT_KEY: &str = "Unit";
+
+/// Resolves the project file together with the configuration it inherits: the file it `extends`
+/// overridden by the fragments it `include`s (in their order) and finally by the project file
+/// itself
+pub fn resolve(local: &Table, file_path: &Path) -> Result<Table> {
+    let mut chain = vec![canonical(file_path)];
+    let mut merged = resolve_inherited(local, file_path, &mut chain)?;
+    
+    merge(&mut merged, local.clone());
+    Ok(merged)
+}
+
+/// Loads the files the table extends and includes (relative to the table's file) and merges
+/// them. `chain` holds the files currently being resolved, which is used to detect cycles
+fn resolve_inherited(table: &Table, file_path: &Path, chain: &mut Vec<PathBuf>) -> Result<Table> {
+    let directory = file_path.parent().unwrap_or(Path::new(""));
+    let mut inherited = Table::new();
+
+    let extends = match table.get(EXTENDS_KEY) {
+        None => None,
+        Some(Value::String(path)) => Some(path.clone()),
+        Some(_) => return Err(invalid_key(EXTENDS_KEY, "a path")),
+    };
+    let include = match table.get(INCLUDE_KEY) {
+        None => Vec::new(),
+        Some(Value::Array(paths)) => paths.iter()
+            .map(|path| path.as_str().map(String::from).ok_or_else(|| invalid_key(INCLUDE_KEY, "a list of paths")))
+            .collect::<Result<_>>()?,
+        Some(_) => return Err(invalid_key(INCLUDE_KEY, "a list of paths")),
+    };
+
+    for path in extends.iter().chain(&include) {
+        let parent = load(&directory.join(path), chain)?;
+        merge(&mut inherited, parent);
+    }
+
+    Ok(inherited)
+}
+
+/// Loads the file together with everything it inherits
+fn load(file_path: &Path, chain: &mut Vec<PathBuf>) -> Result<Table> {
+    let canonical_path = canonical(file_path);
+    if let Some(position) = chain.iter().position(|path| *path == canonical_path) {
+        let cycle: Vec<String> = chain[position..].iter()
+            .chain([&canonical_path])
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(Error::CompositionCycle(cycle.join(" -> ")).into());
+    }
+
+    let data = fs::read_to_string(file_path)
+        .with_context(|| format!("reading {}", file_path.display()))?;
+    let mut table: Table = toml::from_str(&data)
+        .map_err(|err| Error::InvalidProjectFile(err.to_string()))
+        .with_context(|| format!("parsing {}", file_path.display()))?;
+
+    chain.push(canonical_path);
+    let mut inherited = resolve_inherited(&table, file_path, chain)
+        .with_context(|| format!("resolving {}", file_path.display()))?;
+    chain.pop();
+
+    table.remove(EXTENDS_KEY);
+    table.remove(INCLUDE_KEY);
+    merge(&mut inherited, table);
+    Ok(inherited)
+}
+
+/// Merges the overriding table into the base table. Tables are merged key by key, units are
+/// merged by their names (overriding units replace the base units with the same name) and all
+/// the other values are replaced
+fn merge(base: &mut Table, overriding: Table) {
+    for (key, value) in overriding {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(table)) => merge(base_table, table),
+            (Some(Value::Array(base_units)), Value::Array(units)) if key == UNIT_KEY => {
+                for unit in units {
+                    let name = unit.get("name").cloned();
+                    match base_units.iter_mut().find(|base_unit| name.is_some() && base_unit.get("name") == name.as_ref()) {
+                        Some(base_unit) => *base_unit = unit,
+                        None => base_units.push(unit),
+                    }
+                }
+            },
+            (_, value) => {
+                base.insert(key, value);
+            },
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn invalid_key(key: &str, expected: &str) -> crate::error::Error {
+    Error::InvalidProjectFile(format!("'{}' has to be {}", key, expected)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    fn table(data: &str) -> Table {
+        toml::from_str(data).unwrap()
+    }
+
+    #[test]
+    fn merges_tables_and_units() {
+        let mut base = table(r#"
+            name = "base"
+            language = "c"
+            defines = { A = 1, B = 2 }
+
+            [[Unit]]
+            name = "app"
+            type = "binary"
+
+            [[Unit]]
+            name = "lib"
+            type = "static-library"
+        "#);
+        merge(&mut base, table(r#"
+            name = "local"
+            defines = { B = 3 }
+
+            [[Unit]]
+            name = "lib"
+            type = "dynamic-library"
+
+            [[Unit]]
+            name = "tool"
+            type = "binary"
+        "#));
+
+        assert_eq!(base, table(r#"
+            name = "local"
+            language = "c"
+            defines = { A = 1, B = 3 }
+
+            [[Unit]]
+            name = "app"
+            type = "binary"
+
+            [[Unit]]
+            name = "lib"
+            type = "dynamic-library"
+
+            [[Unit]]
+            name = "tool"
+            type = "binary"
+        "#));
+    }
+
+    #[test]
+    fn resolves_extended_and_included_files() {
+        let directory = env::temp_dir().join(format!("copper-compose-{}", process::id()));
+        fs::create_dir_all(directory.join("shared")).unwrap();
+        fs::write(directory.join("shared/base.toml"), "language = 'c'\ndefines = { LEVEL = 1, BASE = 1 }\n").unwrap();
+        fs::write(directory.join("fragment.toml"), "defines = { LEVEL = 2 }\n").unwrap();
+        fs::write(directory.join("cycle.toml"), "extends = 'copper.toml'\n").unwrap();
+
+        let file_path = directory.join("copper.toml");
+        let local = table("extends = 'shared/base.toml'\ninclude = ['fragment.toml']\nname = 'app'\n");
+        let merged = resolve(&local, &file_path).unwrap();
+        
+        let cyclic = resolve(&table("extends = 'cycle.toml'"), &file_path);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(merged.get("name"), Some(&Value::from("app")));
+        assert_eq!(merged.get("language"), Some(&Value::from("c")));
+        assert_eq!(merged.get("defines"), Some(&Value::Table(table("LEVEL = 2\nBASE = 1"))));
+        assert!(matches!(
+            cyclic.as_ref().map_err(|err| err.kind()),
+            Err(crate::error::ErrorKind::Config(Error::CompositionCycle(_)))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Table of preprocessor macro definitions (macro name to its value)
+pub type Defines = BTreeMap<String, DefineValue>;
+
+/// Value of a preprocessor macro definition
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum DefineValue {
+    /// `true` defines the macro without a value, `false` leaves it undefined
+    Flag(bool),
+    /// Integer value of the macro
+    Integer(i64),
+    /// Value of the macro which is passed to the compiler as-is
+    Text(String),
+}
+
+/// Renders macro definitions into `NAME` or `NAME=VALUE` strings, which are passed to the compiler
+/// as separate arguments without going through a shell
+pub fn render_defines(defines: &Defines) -> Vec<String> {
+    defines.iter()
+        .filter_map(|(name, value)| match value {
+            DefineValue::Flag(true) => Some(name.clone()),
+            DefineValue::Flag(false) => None,
+            DefineValue::Integer(i) => Some(format!("{}={}", name, i)),
+            DefineValue::Text(s) => Some(format!("{}={}", name, s)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_each_kind_of_value() {
+        let defines = Defines::from([
+            ("ENABLED".to_string(), DefineValue::Flag(true)),
+            ("DISABLED".to_string(), DefineValue::Flag(false)),
+            ("LEVEL".to_string(), DefineValue::Integer(-2)),
+            ("MESSAGE".to_string(), DefineValue::Text(r#""say "hi" there""#.to_string())),
+        ]);
+
+        assert_eq!(render_defines(&defines), [
+            "ENABLED",
+            "LEVEL=-2",
+            r#"MESSAGE="say "hi" there""#,
+        ]);
+    }
+}
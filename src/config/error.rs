@@ -8,6 +8,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     /// The `copper.toml` configuration file was not found
     ProjectNotFound,
+    /// The `copper.toml` configuration file is not valid (contains the parsing error)
+    InvalidProjectFile(String),
+    /// The project configuration cannot be serialized (contains the serialization error)
+    SerializationFailed(String),
     /// Specified unit was not found in the list of the existing units
     UnitNotFound(String),
     /// The specified language doesn't exist
@@ -18,17 +22,62 @@ pub enum Error {
     InvalidUnitType(String),
     /// There are no units to build
     NoUnits,
+    /// Units depend on each other in a cycle (contains the chain of units forming the cycle)
+    DependencyCycle(String),
+    /// Unit depends on a unit which doesn't exist (unit name and dependency name)
+    DependencyNotFound(String, String),
+    /// The specified build profile doesn't exist
+    ProfileNotFound(String),
+    /// Build profiles inherit each other in a cycle (contains the chain of profiles forming the
+    /// cycle)
+    ProfileInheritanceCycle(String),
+    /// The unit to run was not specified and cannot be determined
+    RunUnitNotSpecified,
+    /// The specified unit cannot be run, since it is not a binary
+    UnitNotRunnable(String),
+    /// Path produced from the project configuration points outside of the project root
+    PathOutsideProject(String),
+    /// Source file glob pattern is not valid
+    InvalidGlobPattern(String),
+    /// Project files extend or include each other in a cycle (contains the chain of files forming
+    /// the cycle)
+    CompositionCycle(String),
+    /// Condition of the conditional configuration section is not valid (condition and the reason)
+    InvalidCondition(String, String),
+    /// The pkg-config executable was not found
+    PkgConfigNotFound(String),
+    /// The pkg-config executable cannot be executed (executable and the error)
+    PkgConfigFailed(String, String),
+    /// The package required by the unit is not available through pkg-config, either missing or not
+    /// satisfying the version constraint (package and the pkg-config error)
+    PackageNotFound(String, String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::ProjectNotFound => write!(f, "Copper project was not found. Create a new one with 'copper init'"),
+            Error::InvalidProjectFile(s) => write!(f, "Invalid project file ({})", s.trim_end()),
+            Error::SerializationFailed(s) => write!(f, "Unable to serialize project ({})", s),
             Error::UnitNotFound(s) => write!(f, "Unit '{}' was not found in project", s),
             Error::InvalidLanguage(s) => write!(f, "Invalid language value provided ('{}')", s),
             Error::InvalidCompiler(s) => write!(f, "Invalid compiler value provided ('{}')", s),
             Error::InvalidUnitType(s) => write!(f, "Invalid unit type value provided ('{}')", s),
             Error::NoUnits => write!(f, "There are no units to build"),
+            Error::DependencyCycle(s) => write!(f, "Units have a cyclic dependency ({})", s),
+            Error::ProfileNotFound(s) => write!(f, "Build profile '{}' was not found in project", s),
+            Error::ProfileInheritanceCycle(s) => write!(f, "Build profiles have a cyclic inheritance ({})", s),
+            Error::RunUnitNotSpecified => write!(f, "Unable to determine which unit to run. Specify the unit or set 'default-run' in project"),
+            Error::UnitNotRunnable(s) => write!(f, "Unit '{}' is not a binary and cannot be run", s),
+            Error::PathOutsideProject(s) => write!(f, "Path '{}' is located outside of the project root", s),
+            Error::InvalidGlobPattern(s) => write!(f, "Invalid glob pattern '{}' (unclosed character class)", s),
+            Error::CompositionCycle(s) => write!(f, "Project files extend or include each other in a cycle ({})", s),
+            Error::InvalidCondition(c, r) => write!(f, "Invalid condition '{}' ({})", c, r),
+            Error::PkgConfigNotFound(s) => write!(f, "Unable to find '{}', which is needed to query pkg-config packages. Install pkg-config or set the PKG_CONFIG variable", s),
+            Error::PkgConfigFailed(s, e) => write!(f, "Unable to execute '{}' ({})", s, e),
+            Error::PackageNotFound(p, e) if e.is_empty() => write!(f, "Package '{}' is not available through pkg-config (check PKG_CONFIG_PATH)", p),
+            Error::PackageNotFound(p, e) => write!(f, "Package '{}' is not available through pkg-config (check PKG_CONFIG_PATH): {}", p, e),
+            Error::DependencyNotFound(u, d) => write!(f, "Unit '{}' depends on unit '{}', which was not found in project", u, d),
         }
     }
 }
\ No newline at end of file
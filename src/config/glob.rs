@@ -0,0 +1,173 @@
+//! Glob patterns which select unit source files relative to the unit source directory
+
+use std::path::{Component, Path};
+use super::{Error, Result};
+
+/// Compiled glob pattern. Patterns consist of `/`-separated segments, where `**` matches any
+/// number of directories (including none) and other segments may contain `*` (any characters),
+/// `?` (any single character) and `[...]` character classes (`[!...]` for negation)
+#[derive(Debug)]
+pub struct Glob {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Segment {
+    /// `**` segment matching any number of path components
+    AnyComponents,
+    Component(Vec<Token>),
+}
+
+#[derive(Debug)]
+enum Token {
+    Char(char),
+    AnyChar,
+    AnyChars,
+    /// Character class with its inclusive ranges and whether it is negated
+    Class(Vec<(char, char)>, bool),
+}
+
+impl Glob {
+    /// Parses the glob pattern. Returns an error if a character class is not closed
+    pub fn new(pattern: &str) -> Result<Self> {
+        let segments = pattern.split('/')
+            .filter(|segment| !segment.is_empty() && *segment != ".")
+            .map(|segment| match segment {
+                "**" => Ok(Segment::AnyComponents),
+                _ => Self::parse_segment(segment)
+                    .map(Segment::Component)
+                    .ok_or_else(|| Error::InvalidGlobPattern(pattern.to_string())),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Glob { segments })
+    }
+
+    /// Returns whether the path (relative to the unit source directory) matches the pattern
+    pub fn matches(&self, path: &Path) -> bool {
+        let components: Option<Vec<&str>> = path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .map(|component| match component {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        components.is_some_and(|components| Self::match_segments(&self.segments, &components))
+    }
+
+    fn parse_segment(segment: &str) -> Option<Vec<Token>> {
+        let mut tokens = Vec::new();
+        let mut chars = segment.chars();
+
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '*' => Token::AnyChars,
+                '?' => Token::AnyChar,
+                '[' => {
+                    let mut class: Vec<char> = Vec::new();
+                    loop {
+                        match chars.next()? {
+                            // A closing bracket right after the opening one is a part of the class
+                            ']' if !class.is_empty() && class != ['!'] => break,
+                            c => class.push(c),
+                        }
+                    }
+                    let negated = class.first() == Some(&'!');
+                    let class = if negated { &class[1..] } else { &class[..] };
+
+                    let mut ranges = Vec::new();
+                    let mut i = 0;
+                    while i < class.len() {
+                        if i + 2 < class.len() && class[i + 1] == '-' {
+                            ranges.push((class[i], class[i + 2]));
+                            i += 3;
+                        } else {
+                            ranges.push((class[i], class[i]));
+                            i += 1;
+                        }
+                    }
+                    Token::Class(ranges, negated)
+                },
+                c => Token::Char(c),
+            };
+            tokens.push(token);
+        }
+
+        Some(tokens)
+    }
+
+    fn match_segments(segments: &[Segment], components: &[&str]) -> bool {
+        match segments.first() {
+            None => components.is_empty(),
+            Some(Segment::AnyComponents) => Self::match_segments(&segments[1..], components)
+                || (!components.is_empty() && Self::match_segments(segments, &components[1..])),
+            Some(Segment::Component(tokens)) => !components.is_empty()
+                && Self::match_tokens(tokens, &components[0].chars().collect::<Vec<_>>())
+                && Self::match_segments(&segments[1..], &components[1..]),
+        }
+    }
+
+    fn match_tokens(tokens: &[Token], chars: &[char]) -> bool {
+        match tokens.first() {
+            None => chars.is_empty(),
+            Some(Token::AnyChars) => (0..=chars.len())
+                .any(|skipped| Self::match_tokens(&tokens[1..], &chars[skipped..])),
+            Some(token) => {
+                let Some(&c) = chars.first() else { return false };
+                let matched = match token {
+                    Token::Char(expected) => c == *expected,
+                    Token::AnyChar => true,
+                    Token::Class(ranges, negated) => ranges.iter()
+                        .any(|(start, end)| (*start..=*end).contains(&c)) != *negated,
+                    Token::AnyChars => unreachable!(),
+                };
+                matched && Self::match_tokens(&tokens[1..], &chars[1..])
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        Glob::new(pattern).unwrap().matches(Path::new(path))
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(matches("*.c", "main.c"));
+        assert!(matches("./*.c", "./main.c"));
+        assert!(!matches("*.c", "net/main.c"));
+        assert!(!matches("*.c", "main.cpp"));
+        assert!(matches("net/?.c", "net/a.c"));
+        assert!(!matches("net/?.c", "net/ab.c"));
+        assert!(matches("test_*_unix.c", "test_io_unix.c"));
+    }
+
+    #[test]
+    fn matches_any_directories() {
+        assert!(matches("**/*.c", "main.c"));
+        assert!(matches("**/*.c", "net/tcp/socket.c"));
+        assert!(matches("net/**", "net/tcp/socket.c"));
+        assert!(matches("net/**/socket.c", "net/socket.c"));
+        assert!(!matches("net/**/socket.c", "io/socket.c"));
+    }
+
+    #[test]
+    fn matches_character_classes() {
+        assert!(matches("file[0-9].c", "file7.c"));
+        assert!(!matches("file[0-9].c", "filex.c"));
+        assert!(matches("file[!0-9].c", "filex.c"));
+        assert!(matches("[]x].c", "].c"));
+        assert!(matches("[a-].c", "-.c"));
+    }
+
+    #[test]
+    fn rejects_unclosed_classes() {
+        assert!(matches!(Glob::new("src/[abc.c"), Err(Error::InvalidGlobPattern(pattern)) if pattern == "src/[abc.c"));
+        assert!(Glob::new("[!]").is_err());
+    }
+}
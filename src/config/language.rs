@@ -1,10 +1,11 @@
 use std::fmt::Display;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use serde::{Deserialize, Serialize};
-use crate::error::Error;
+use super::Error;
 
 /// Enum representing available project languages
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(try_from = "String", into = "String")]
 pub enum ProjectLanguage {
     C,
@@ -15,7 +16,8 @@ impl ProjectLanguage {
     const C_STR: &'static str = "c";
     const CPP_STR: &'static str = "c++";
     const C_EXTENSIONS: [&'static str; 1] = ["c"];
-    const CPP_EXTENSIONS: [&'static str; 2] = ["c", "cpp"];
+    /// C++ projects may contain C sources as well, which are still compiled as C
+    const CPP_EXTENSIONS: [&'static str; 6] = ["c", "cc", "cpp", "cxx", "c++", "C"];
 
     /// Returns an array of possible unit type variants as stings
     pub fn str_variants() -> [&'static str; 2] {
@@ -31,6 +33,18 @@ impl ProjectLanguage {
             .map(OsString::from)
             .collect()
     }
+    
+    /// Returns the language of the source file with the specified extension, or `None` if the
+    /// extension doesn't belong to any source file
+    pub fn from_extension(extension: &OsStr) -> Option<Self> {
+        if Self::C_EXTENSIONS.iter().any(|ext| extension == *ext) {
+            Some(ProjectLanguage::C)
+        } else if Self::CPP_EXTENSIONS.iter().any(|ext| extension == *ext) {
+            Some(ProjectLanguage::CPP)
+        } else {
+            None
+        }
+    }
 }
 
 impl TryFrom<String> for ProjectLanguage {
@@ -40,16 +54,16 @@ impl TryFrom<String> for ProjectLanguage {
         match value.to_lowercase().trim() {
             Self::C_STR => Ok(ProjectLanguage::C),
             Self::CPP_STR => Ok(ProjectLanguage::CPP),
-            _ => Err(Error::EnumParseError(format!("Unexpected language value: {}", value)))
+            _ => Err(Error::InvalidLanguage(value))
         }
     }
 }
 
-impl Into<String> for ProjectLanguage {
-    fn into(self) -> String {
-        match self {
-            ProjectLanguage::C => Self::C_STR.to_string(),
-            ProjectLanguage::CPP => Self::CPP_STR.to_string()
+impl From<ProjectLanguage> for String {
+    fn from(value: ProjectLanguage) -> Self {
+        match value {
+            ProjectLanguage::C => ProjectLanguage::C_STR.to_string(),
+            ProjectLanguage::CPP => ProjectLanguage::CPP_STR.to_string()
         }
     }
 }
@@ -0,0 +1,39 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Library which is linked into the unit in addition to the project's own libraries
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum LinkLibrary {
+    /// Library searched by its name in the library paths (e.g. `m` for `-lm`)
+    Name(String),
+    /// Library file specified by its path relative to the project root (any value containing a
+    /// path separator, e.g. `third_party/libfoo.a`)
+    File(PathBuf),
+}
+
+impl From<String> for LinkLibrary {
+    fn from(value: String) -> Self {
+        if value.contains(['/', '\\']) {
+            LinkLibrary::File(PathBuf::from(value))
+        } else {
+            LinkLibrary::Name(value)
+        }
+    }
+}
+
+impl From<LinkLibrary> for String {
+    fn from(value: LinkLibrary) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for LinkLibrary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkLibrary::Name(name) => write!(f, "{}", name),
+            LinkLibrary::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
@@ -5,16 +5,30 @@ mod project;
 mod error;
 mod language;
 mod compiler;
+mod define;
+mod profile;
+mod run;
+mod glob;
+mod link;
+mod pkg_config;
+mod target;
+mod compose;
 
 pub use project::ProjectConfig;
 pub use unit::{UnitConfig, UnitType};
 pub use language::ProjectLanguage;
 pub use compiler::ProjectCompiler;
+pub use define::{Defines, DefineValue, render_defines};
+pub use profile::ProfileConfig;
+pub use run::RunConfig;
+pub use link::LinkLibrary;
 pub use error::{Error, Result};
 
 pub const PROJECT_FILE_NAME: &str = "copper.toml";
-#[allow(dead_code)]
 pub const PROJECT_DIRECTORY_NAME: &str = ".copper";
+/// Directories (under the install prefix) which binaries and libraries are installed to
+pub const INSTALL_BINARY_DIRECTORY_NAME: &str = "bin";
+pub const INSTALL_LIBRARY_DIRECTORY_NAME: &str = "lib";
 
 /// Contains default values for project configuration
 pub mod default {
@@ -0,0 +1,170 @@
+//! Queries pkg-config for the compiler and linker flags of the system packages units depend on
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+use super::{DefineValue, Defines, Error, LinkLibrary, Result};
+
+/// Environment variable which overrides the pkg-config executable (e.g. when cross-compiling)
+const PKG_CONFIG_VARIABLE: &str = "PKG_CONFIG";
+const PKG_CONFIG_DEFAULT: &str = "pkg-config";
+
+/// Flags of the packages sorted by how they are used by Copper
+#[derive(Debug, Default)]
+pub struct PackageFlags {
+    pub include_paths: Vec<PathBuf>,
+    pub defines: Defines,
+    /// Compiler flags other than include paths and definitions
+    pub compile_args: Vec<String>,
+    pub library_paths: Vec<PathBuf>,
+    pub link_libraries: Vec<LinkLibrary>,
+    /// Linker flags other than library paths and libraries
+    pub linker_args: Vec<String>,
+}
+
+/// Returns the flags required to compile and link against the packages. Packages may contain
+/// version constraints (e.g. "libpng >= 1.6"). pkg-config searches the packages in its default
+/// locations and in the directories listed in `PKG_CONFIG_PATH`
+pub fn query(packages: &[String]) -> Result<PackageFlags> {
+    query_with_env(packages, &[])
+}
+
+/// Queries the packages with additional environment variables set for pkg-config
+fn query_with_env(packages: &[String], env: &[(&str, &str)]) -> Result<PackageFlags> {
+    let mut flags = PackageFlags::default();
+    if packages.is_empty() {
+        return Ok(flags);
+    }
+
+    // Every package is checked separately, so the error names the package which is missing
+    for package in packages {
+        let output = run(&["--exists", "--print-errors", package], env)?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(Error::PackageNotFound(package.clone(), message));
+        }
+    }
+
+    for arg in query_flags("--cflags", packages, env)? {
+        if let Some(path) = arg.strip_prefix("-I") {
+            flags.include_paths.push(PathBuf::from(path));
+        } else if let Some(define) = arg.strip_prefix("-D") {
+            match define.split_once('=') {
+                Some((name, value)) => flags.defines.insert(name.to_string(), DefineValue::Text(value.to_string())),
+                None => flags.defines.insert(define.to_string(), DefineValue::Flag(true)),
+            };
+        } else {
+            flags.compile_args.push(arg);
+        }
+    }
+
+    for arg in query_flags("--libs", packages, env)? {
+        if let Some(path) = arg.strip_prefix("-L") {
+            flags.library_paths.push(PathBuf::from(path));
+        } else if let Some(name) = arg.strip_prefix("-l") {
+            flags.link_libraries.push(LinkLibrary::Name(name.to_string()));
+        } else {
+            flags.linker_args.push(arg);
+        }
+    }
+
+    Ok(flags)
+}
+
+/// Queries the flags of all the packages with the pkg-config option (`--cflags` or `--libs`)
+fn query_flags(option: &str, packages: &[String], env: &[(&str, &str)]) -> Result<Vec<String>> {
+    let args: Vec<&str> = std::iter::once(option).chain(packages.iter().map(String::as_str)).collect();
+    let output = run(&args, env)?;
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(Error::PackageNotFound(packages.join(", "), message));
+    }
+
+    Ok(split_flags(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn run(args: &[&str], env: &[(&str, &str)]) -> Result<std::process::Output> {
+    let program = env::var(PKG_CONFIG_VARIABLE).unwrap_or_else(|_| PKG_CONFIG_DEFAULT.to_string());
+
+    Command::new(&program)
+        .args(args)
+        .envs(env.iter().copied())
+        .output()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => Error::PkgConfigNotFound(program),
+            _ => Error::PkgConfigFailed(program, err.to_string()),
+        })
+}
+
+/// Splits the flags printed by pkg-config, which escapes the special characters in them with
+/// backslashes and may quote them
+fn split_flags(output: &str) -> Vec<String> {
+    let mut flags = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    let mut chars = output.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                if let Some(escaped) = chars.next() {
+                    current.get_or_insert_with(String::new).push(escaped);
+                }
+            },
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            },
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => flags.extend(current.take()),
+            (c, _) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    flags.extend(current);
+
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_escaped_and_quoted_flags() {
+        assert_eq!(
+            split_flags("-I/usr/include  -DNAME=\\\"x\\\" -L/opt/my\\ lib '-Wl,a b' \"-DQ='v'\"\n"),
+            ["-I/usr/include", "-DNAME=\"x\"", "-L/opt/my lib", "-Wl,a b", "-DQ='v'"],
+        );
+        assert!(split_flags(" \n").is_empty());
+        assert_eq!(split_flags("''"), [""]);
+    }
+
+    #[test]
+    fn queries_fixture_package() {
+        // The packages are looked up only in the fixture directory
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/pkgconfig");
+        let env = [("PKG_CONFIG_PATH", fixtures), ("PKG_CONFIG_LIBDIR", fixtures)];
+        
+        // pkg-config is not required to build Copper, so there is nothing to test without it
+        if let Err(Error::PkgConfigNotFound(_)) = run(&["--version"], &env) {
+            return;
+        }
+        
+        let flags = query_with_env(&["fake >= 1.5".to_string()], &env).unwrap();
+        assert_eq!(flags.include_paths, [PathBuf::from("/opt/fake/include")]);
+        assert_eq!(flags.defines, Defines::from([
+            ("FAKE_ON".to_string(), DefineValue::Flag(true)),
+            ("FAKE_NAME".to_string(), DefineValue::Text("\"fake\"".to_string())),
+        ]));
+        assert_eq!(flags.compile_args, ["-pthread"]);
+        assert_eq!(flags.library_paths, [PathBuf::from("/opt/fake/my lib")]);
+        assert_eq!(flags.link_libraries, [LinkLibrary::Name("fake".to_string())]);
+        assert_eq!(flags.linker_args, ["-pthread"]);
+        
+        assert!(query_with_env(&[], &env).unwrap().include_paths.is_empty());
+        assert!(matches!(query_with_env(&["fake >= 2".to_string()], &env), Err(Error::PackageNotFound(package, _)) if package == "fake >= 2"));
+        assert!(matches!(query_with_env(&["missing".to_string()], &env), Err(Error::PackageNotFound(package, _)) if package == "missing"));
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+use super::{Defines, DefineValue, Error, Result};
+
+/// Configuration of a build profile, which specifies the optimization level, debug information and
+/// additional profile-specific arguments. Unset values are taken from the inherited profile
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProfileConfig {
+    /// Name of the profile to inherit unset values from
+    pub inherits: Option<String>,
+    /// Optimization level passed to the compiler (e.g. `0`, `2`, `"s"`)
+    pub optimization: Option<OptimizationLevel>,
+    /// Whether to generate debug information
+    pub debug_info: Option<bool>,
+    /// Profile-specific preprocessor definitions
+    pub defines: Option<Defines>,
+    /// Profile-specific additional compiler arguments
+    pub additional_compiler_args: Option<String>,
+    /// Profile-specific additional linker arguments (split on whitespace)
+    pub additional_linker_args: Option<String>,
+    /// Profile-specific linker arguments, each passed to the linker as-is
+    pub linker_args: Option<Vec<String>>,
+}
+
+impl ProfileConfig {
+    pub const DEBUG: &'static str = "debug";
+    pub const RELEASE: &'static str = "release";
+    
+    /// Returns the built-in configuration of the profile with the specified name, if there is one
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            Self::DEBUG => Some(ProfileConfig {
+                optimization: Some(OptimizationLevel::Level(0)),
+                debug_info: Some(true),
+                ..Default::default()
+            }),
+            Self::RELEASE => Some(ProfileConfig {
+                optimization: Some(OptimizationLevel::Level(3)),
+                debug_info: Some(false),
+                defines: Some(Defines::from([("NDEBUG".to_string(), DefineValue::Flag(true))])),
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+    
+    /// Returns a copy of self with all the unset values taken from the parent profile. Definitions
+    /// are merged, with self's definitions overriding the parent's ones
+    fn inherit_from(&self, parent: &ProfileConfig) -> Self {
+        let defines = match (&parent.defines, &self.defines) {
+            (Some(parent_defines), Some(defines)) => {
+                let mut merged = parent_defines.clone();
+                merged.extend(defines.clone());
+                Some(merged)
+            },
+            (parent_defines, defines) => defines.clone().or(parent_defines.clone()),
+        };
+        
+        ProfileConfig {
+            inherits: self.inherits.clone().or(parent.inherits.clone()),
+            optimization: self.optimization.clone().or(parent.optimization.clone()),
+            debug_info: self.debug_info.or(parent.debug_info),
+            defines,
+            additional_compiler_args: self.additional_compiler_args.clone().or(parent.additional_compiler_args.clone()),
+            additional_linker_args: self.additional_linker_args.clone().or(parent.additional_linker_args.clone()),
+            linker_args: self.linker_args.clone().or(parent.linker_args.clone()),
+        }
+    }
+    
+    /// Resolves the profile with the specified name from the user-defined profiles and the built-in
+    /// ones. User-defined `debug` and `release` profiles extend the built-in ones. Returns the
+    /// profile with all the inherited values applied
+    pub fn resolve(name: &str, profiles: &BTreeMap<String, ProfileConfig>) -> Result<Self> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut resolved = ProfileConfig::default();
+        let mut current = Some(name.to_string());
+        
+        while let Some(profile_name) = current {
+            if chain.contains(&profile_name) {
+                chain.push(profile_name);
+                return Err(Error::ProfileInheritanceCycle(chain.join(" -> ")));
+            }
+            
+            let profile = match (profiles.get(&profile_name), Self::builtin(&profile_name)) {
+                (Some(profile), Some(builtin)) => profile.inherit_from(&builtin),
+                (Some(profile), None) => profile.clone(),
+                (None, Some(builtin)) => builtin,
+                (None, None) => return Err(Error::ProfileNotFound(profile_name)),
+            };
+            
+            resolved = resolved.inherit_from(&profile);
+            current = profile.inherits;
+            chain.push(profile_name);
+        }
+        
+        Ok(resolved)
+    }
+}
+
+/// Optimization level of the profile, either a number (`0`-`3`) or a named level (`"s"`, `"z"`,
+/// `"g"`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum OptimizationLevel {
+    Level(u8),
+    Named(String),
+}
+
+impl Display for OptimizationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptimizationLevel::Level(level) => write!(f, "{}", level),
+            OptimizationLevel::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_profiles(contents: &str) -> BTreeMap<String, ProfileConfig> {
+        toml::from_str(contents).unwrap()
+    }
+
+    #[test]
+    fn overrides_builtin_release_keeping_unset_values() {
+        let profiles = parse_profiles(r#"
+            [release]
+            optimization = "s"
+            linker-args = ["-s"]
+        "#);
+
+        let release = ProfileConfig::resolve(ProfileConfig::RELEASE, &profiles).unwrap();
+
+        assert_eq!(release.optimization, Some(OptimizationLevel::Named("s".to_string())));
+        assert_eq!(release.debug_info, Some(false));
+        assert_eq!(release.linker_args, Some(vec!["-s".to_string()]));
+        assert_eq!(release.defines, Some(Defines::from([("NDEBUG".to_string(), DefineValue::Flag(true))])));
+    }
+
+    #[test]
+    fn custom_profile_inherits_release_and_merges_defines_along_chain() {
+        let profiles = parse_profiles(r#"
+            [release]
+            defines = { LOG_LEVEL = 2 }
+
+            [fast]
+            inherits = "release"
+            debug-info = true
+            defines = { LOG_LEVEL = 0, FAST = "yes" }
+
+            [profiling]
+            inherits = "fast"
+            defines = { NDEBUG = false }
+        "#);
+
+        let profiling = ProfileConfig::resolve("profiling", &profiles).unwrap();
+
+        assert_eq!(profiling.optimization, Some(OptimizationLevel::Level(3)));
+        assert_eq!(profiling.debug_info, Some(true));
+        assert_eq!(profiling.defines, Some(Defines::from([
+            ("NDEBUG".to_string(), DefineValue::Flag(false)),
+            ("LOG_LEVEL".to_string(), DefineValue::Integer(0)),
+            ("FAST".to_string(), DefineValue::Text("yes".to_string())),
+        ])));
+    }
+
+    #[test]
+    fn reports_inheritance_cycle_and_missing_profile() {
+        let profiles = parse_profiles(r#"
+            [first]
+            inherits = "second"
+
+            [second]
+            inherits = "first"
+
+            [orphan]
+            inherits = "missing"
+        "#);
+
+        match ProfileConfig::resolve("first", &profiles) {
+            Err(Error::ProfileInheritanceCycle(cycle)) => assert_eq!(cycle, "first -> second -> first"),
+            result => panic!("expected an inheritance cycle, got {:?}", result),
+        }
+        match ProfileConfig::resolve("orphan", &profiles) {
+            Err(Error::ProfileNotFound(name)) => assert_eq!(name, "missing"),
+            result => panic!("expected a missing profile, got {:?}", result),
+        }
+        match ProfileConfig::resolve("unknown", &profiles) {
+            Err(Error::ProfileNotFound(name)) => assert_eq!(name, "unknown"),
+            result => panic!("expected a missing profile, got {:?}", result),
+        }
+    }
+}
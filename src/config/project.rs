@@ -1,10 +1,13 @@
-use std::process;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use crate::compiler::CompilerOptions;
-use super::{default, equals, ProjectLanguage, ProjectCompiler, UnitConfig, UnitType, PROJECT_FILE_NAME};
+use crate::error::Context;
+use super::compose;
+use super::target::{Condition, TargetConfig, TargetUnitConfig};
+use super::{default, equals, Defines, LinkLibrary, ProjectLanguage, ProjectCompiler, ProfileConfig, UnitConfig, UnitType, Error, Result, PROJECT_FILE_NAME};
 
 /// Main Copper project configuration file
 #[derive(Debug, Serialize, Deserialize)]
@@ -13,6 +16,16 @@ pub struct ProjectConfig {
     /// Location of the Copper project relative to where the command was executed.
     #[serde(skip)]
     pub project_location: PathBuf,
+    /// Project file (relative to this file) whose configuration this project extends
+    extends: Option<PathBuf>,
+    /// Configuration fragments (relative to this file) which are included into this project. They
+    /// override the extended file and are overridden by this file
+    include: Option<Vec<PathBuf>>,
+    /// Contents of the project file as they were imported (without the configuration inherited
+    /// from the extended and included files). Changes to the project are applied to them, so the
+    /// file is saved back as it was written
+    #[serde(skip)]
+    local: Option<toml::Table>,
     /// Name of the project
     name: String,
     /// Chosen language for the project
@@ -39,6 +52,29 @@ pub struct ProjectConfig {
     pub global_include_paths: Option<Vec<PathBuf>>,
     /// Project-wide additional compiler arguments
     pub global_additional_compiler_args: Option<String>,
+    /// Project-wide preprocessor definitions
+    defines: Option<Defines>,
+    /// Libraries linked into every linked unit (after the units' own libraries)
+    link_libraries: Option<Vec<LinkLibrary>>,
+    /// Project-wide additional library search paths
+    library_paths: Option<Vec<PathBuf>>,
+    /// Project-wide arguments passed only to the linker (before the units' own linker arguments)
+    linker_args: Option<Vec<String>>,
+    /// Name of the binary unit which is executed by `copper run` when no unit is specified
+    default_run: Option<String>,
+    /// User-defined build profiles (including overrides of the built-in `debug` and `release`
+    /// profiles)
+    #[serde(rename = "profile")]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profiles: BTreeMap<String, ProfileConfig>,
+    /// Conditional configuration sections by their `cfg(...)` conditions, which extend the project
+    /// and its units
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    target: BTreeMap<String, TargetConfig>,
+    /// Conditions of the sections which hold for the host platform and the project's compiler
+    /// (evaluated when the project is imported)
+    #[serde(skip)]
+    active_targets: Vec<String>,
     /// Unit configuration data
     #[serde(rename = "Unit")]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -57,6 +93,9 @@ impl ProjectConfig {
     ) -> Self {
         ProjectConfig {
             project_location,
+            extends: None,
+            include: None,
+            local: None,
             name,
             language,
             compiler,
@@ -66,56 +105,143 @@ impl ProjectConfig {
             default_object_directory: default::OBJECT_DIRECTORY(),
             global_include_paths,
             global_additional_compiler_args: global_compiler_args,
+            defines: None,
+            link_libraries: None,
+            library_paths: None,
+            linker_args: None,
+            default_run: None,
+            profiles: BTreeMap::new(),
+            target: BTreeMap::new(),
+            active_targets: Vec::new(),
             units,
         }
     }
 
     /// Imports a Copper project from a .toml project file
-    pub fn import(directory: &Path) -> io::Result<Self> {
+    pub fn import(directory: &Path) -> crate::error::Result<Self> {
         let file_path = directory.join(PROJECT_FILE_NAME);
-        let mut file = File::open(file_path)?;
+        let mut file = match File::open(&file_path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Err(Error::ProjectNotFound.into()),
+            Err(err) => return Err(err).with_context(|| format!("reading {}", file_path.display())),
+        };
 
         let mut file_data = String::new();
-        file.read_to_string(&mut file_data)?;
+        file.read_to_string(&mut file_data)
+            .with_context(|| format!("reading {}", file_path.display()))?;
 
-        let mut project: ProjectConfig = match toml::from_str(&file_data) {
-            Ok(project) => project,
-            Err(err) => {
-                eprintln!("Unable to deserialize project: {}", err);
-                process::exit(1);
-            }
+        let local: toml::Table = toml::from_str(&file_data)
+            .map_err(|err| Error::InvalidProjectFile(err.to_string()))
+            .with_context(|| format!("parsing {}", file_path.display()))?;
+        
+        // Project files which don't inherit anything are parsed directly, so the errors point to
+        // the location in the file
+        let mut project: ProjectConfig = if local.contains_key("extends") || local.contains_key("include") {
+            let merged = compose::resolve(&local, &file_path)?;
+            toml::Value::Table(merged).try_into()
+                .map_err(|err| Error::InvalidProjectFile(err.to_string()))
+                .with_context(|| format!("parsing {} with the files it extends and includes", file_path.display()))?
+        } else {
+            toml::from_str(&file_data)
+                .map_err(|err| Error::InvalidProjectFile(err.to_string()))
+                .with_context(|| format!("parsing {}", file_path.display()))?
         };
 
         project.project_location = directory.to_path_buf();
+        project.local = Some(local);
+        project.evaluate_targets()
+            .with_context(|| format!("evaluating conditional sections of {}", file_path.display()))?;
         Ok(project)
     }
+    
+    /// Evaluates the conditions of the conditional sections and remembers the ones which hold. All
+    /// the sections are validated, so mistakes are found on every platform
+    fn evaluate_targets(&mut self) -> Result<()> {
+        for (condition, section) in &self.target {
+            let holds = Condition::parse(condition)?.evaluate(&self.compiler);
+            
+            if let Some(unit_name) = section.units.keys().find(|name| self.find_unit(name).is_none()) {
+                return Err(Error::UnitNotFound(unit_name.clone()));
+            }
+            
+            if holds {
+                self.active_targets.push(condition.clone());
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Returns the conditional sections whose conditions hold
+    fn get_active_targets(&self) -> impl Iterator<Item = &TargetConfig> {
+        self.active_targets.iter().filter_map(|condition| self.target.get(condition))
+    }
+    
+    /// Returns the conditional additions to the unit from the sections whose conditions hold
+    pub fn get_target_units(&self, unit_name: &str) -> Vec<&TargetUnitConfig> {
+        self.get_active_targets()
+            .filter_map(|section| section.units.get(unit_name))
+            .collect()
+    }
+    
+    /// Returns the project-wide preprocessor definitions (including the conditional ones)
+    pub fn get_defines(&self) -> Defines {
+        let mut defines = self.defines.clone().unwrap_or_default();
+        self.get_active_targets().for_each(|section| defines.extend(section.defines.clone().unwrap_or_default()));
+        defines
+    }
+    
+    /// Returns the libraries linked into every linked unit (including the conditional ones)
+    pub fn get_link_libraries(&self) -> Vec<LinkLibrary> {
+        self.collect_lists(&self.link_libraries, |section| &section.link_libraries)
+    }
+    
+    /// Returns the project-wide library search paths (including the conditional ones)
+    pub fn get_library_paths(&self) -> Vec<PathBuf> {
+        self.collect_lists(&self.library_paths, |section| &section.library_paths)
+    }
+    
+    /// Returns the project-wide linker arguments (including the conditional ones)
+    pub fn get_linker_args(&self) -> Vec<String> {
+        self.collect_lists(&self.linker_args, |section| &section.linker_args)
+    }
+    
+    /// Returns the project's list followed by the lists of the conditional sections
+    fn collect_lists<T: Clone>(&self, list: &Option<Vec<T>>, field: impl Fn(&TargetConfig) -> &Option<Vec<T>>) -> Vec<T> {
+        std::iter::once(list)
+            .chain(self.get_active_targets().map(field))
+            .flatten()
+            .flatten()
+            .cloned()
+            .collect()
+    }
 
-    /// Saves current Copper project to the .toml project file
-    pub fn save(self, directory: &Path) -> io::Result<()> {
+    /// Saves current Copper project to the .toml project file. Imported projects save the file's
+    /// own contents with the changes applied, leaving out the values inherited from the files it
+    /// extends and includes
+    pub fn save(self, directory: &Path) -> crate::error::Result<()> {
         let file_path = directory.join(PROJECT_FILE_NAME);
-        let mut file = File::create(&file_path)?;
 
-        let toml_data = match toml::to_string(&self) {
-            Ok(toml) => toml,
-            Err(err) => {
-                eprintln!("Unable to serialize project: {}", err);
-                process::exit(1);
-            }
-        };
+        let toml_data = match &self.local {
+            Some(local) => toml::to_string(local),
+            None => toml::to_string(&self),
+        }.map_err(|err| Error::SerializationFailed(err.to_string()))?;
 
+        let mut file = File::create(&file_path)
+            .with_context(|| format!("writing {}", file_path.display()))?;
         file.write_all(toml_data.as_bytes())?;
         file.flush()?;
         Ok(())
     }
 
     /// Creates a new unit with minimum configuration and adds it to the project
-    pub fn add_unit(&mut self, unit_name: String, unit_type: UnitType, unit_source: PathBuf) {
+    pub fn add_unit(&mut self, unit_name: String, unit_type: UnitType, unit_source: PathBuf) -> Result<()> {
         let unit_type_directory = match &unit_type {
-            UnitType::Binary => &self.default_binary_directory,
+            UnitType::Binary | UnitType::Test => &self.default_binary_directory,
             UnitType::StaticLibrary | UnitType::DynamicLibrary => &self.default_library_directory,
         };
 
-        self.units.push(UnitConfig::new(
+        let unit = UnitConfig::new(
             unit_name,
             unit_type,
             unit_source,
@@ -123,21 +249,53 @@ impl ProjectConfig {
             self.default_build_directory.join(&self.default_object_directory),
             None,
             None,
-        ))
+        );
+        
+        if let Some(local) = &mut self.local {
+            let value = toml::Value::try_from(&unit)
+                .map_err(|err| Error::SerializationFailed(err.to_string()))?;
+            match local.entry(compose::UNIT_KEY).or_insert_with(|| toml::Value::Array(Vec::new())) {
+                toml::Value::Array(units) => units.push(value),
+                _ => return Err(Error::InvalidProjectFile(format!("'{}' has to be a list of units", compose::UNIT_KEY))),
+            }
+        }
+        
+        self.units.push(unit);
+        Ok(())
     }
 
     /// Searches for a unit in project by the provided name. If not found, returns None
     pub fn find_unit(&self, unit_name: &str) -> Option<&UnitConfig> {
         let unit = self.units.iter()
-            .find(|u| &u.name == unit_name);
+            .find(|u| u.name == unit_name);
 
         if let Some(unit) = unit {
-            Some(&unit)
+            Some(unit)
         } else {
            None
         }
     }
 
+    /// Returns the binary unit which is executed by `copper run`. If the unit name is not specified,
+    /// the project's `default-run` unit or the only binary unit of the project is used
+    pub fn find_run_unit(&self, unit_name: Option<&str>) -> Result<&UnitConfig> {
+        let unit = match unit_name.or(self.default_run.as_deref()) {
+            Some(name) => self.find_unit(name).ok_or(Error::UnitNotFound(name.to_string()))?,
+            None => {
+                let mut binaries = self.units.iter().filter(|unit| matches!(unit.r#type, UnitType::Binary));
+                match (binaries.next(), binaries.next()) {
+                    (Some(unit), None) => unit,
+                    _ => return Err(Error::RunUnitNotSpecified),
+                }
+            }
+        };
+        
+        match unit.r#type {
+            UnitType::Binary => Ok(unit),
+            _ => Err(Error::UnitNotRunnable(unit.name.clone())),
+        }
+    }
+
     /// Returns an iterator containing the names of the all project units
     pub fn get_unit_names(&self) -> Vec<&String> {
         self.units.iter()
@@ -145,12 +303,252 @@ impl ProjectConfig {
         .collect()
     }
     
-    pub fn get_compiler_options(&self) -> CompilerOptions {
+    /// Returns the names of the units which are built by default (all units except the tests)
+    pub fn get_default_unit_names(&self) -> Vec<&String> {
+        self.units.iter()
+            .filter(|unit| !matches!(unit.r#type, UnitType::Test))
+            .map(|unit| &unit.name)
+            .collect()
+    }
+    
+    /// Returns the test units whose names contain the filter (or all test units)
+    pub fn get_test_units(&self, filter: Option<&str>) -> Vec<&UnitConfig> {
+        self.units.iter()
+            .filter(|unit| matches!(unit.r#type, UnitType::Test))
+            .filter(|unit| filter.is_none_or(|filter| unit.name.contains(filter)))
+            .collect()
+    }
+    
+    /// Returns all the units which depend on the specified unit (directly or transitively)
+    pub fn get_dependent_units(&self, unit_name: &str) -> Vec<&UnitConfig> {
+        self.units.iter()
+            .filter(|unit| unit.name != unit_name)
+            .filter(|unit| self.resolve_build_order(&[&unit.name])
+                .is_ok_and(|order| order.iter().any(|u| u.name == unit_name)))
+            .collect()
+    }
+
+    /// Returns the specified units together with all of their dependencies in the order they
+    /// have to be built (every unit comes after all the units it depends on)
+    pub fn resolve_build_order(&self, unit_names: &[&String]) -> Result<Vec<&UnitConfig>> {
+        let mut order = Vec::new();
+        let mut visiting = Vec::new();
+        
+        for unit_name in unit_names {
+            if self.find_unit(unit_name).is_none() {
+                return Err(Error::UnitNotFound(unit_name.to_string()));
+            }
+            self.visit_unit(unit_name, &mut visiting, &mut order)?;
+        }
+        
+        Ok(order)
+    }
+    
+    /// Performs a depth-first visit of the unit's dependencies, appending each unit to the order
+    /// after all of its dependencies. `visiting` holds the chain of the units currently being
+    /// visited, which is used to detect dependency cycles
+    fn visit_unit<'a>(&'a self, unit_name: &str, visiting: &mut Vec<&'a str>, order: &mut Vec<&'a UnitConfig>) -> Result<()> {
+        if order.iter().any(|unit| unit.name == unit_name) {
+            return Ok(());
+        }
+        
+        if let Some(position) = visiting.iter().position(|name| *name == unit_name) {
+            let mut cycle: Vec<&str> = visiting[position..].to_vec();
+            cycle.push(unit_name);
+            return Err(Error::DependencyCycle(cycle.join(" -> ")));
+        }
+        
+        let unit = match self.find_unit(unit_name) {
+            Some(unit) => unit,
+            None => return Err(Error::DependencyNotFound(
+                visiting.last().unwrap_or(&"").to_string(),
+                unit_name.to_string(),
+            )),
+        };
+        
+        visiting.push(&unit.name);
+        for dependency in unit.get_dependencies() {
+            self.visit_unit(dependency, visiting, order)?;
+        }
+        visiting.pop();
+        
+        order.push(unit);
+        Ok(())
+    }
+    
+    /// Returns the build profile with the specified name with all of its inherited values applied
+    pub fn get_profile(&self, profile_name: &str) -> Result<ProfileConfig> {
+        ProfileConfig::resolve(profile_name, &self.profiles)
+    }
+    
+    /// Returns the names of all the available build profiles (built-in and user-defined ones)
+    pub fn get_profile_names(&self) -> Vec<&str> {
+        let mut names = vec![ProfileConfig::DEBUG, ProfileConfig::RELEASE];
+        names.extend(self.profiles.keys()
+            .map(String::as_str)
+            .filter(|name| *name != ProfileConfig::DEBUG && *name != ProfileConfig::RELEASE));
+        names
+    }
+    
+    pub fn get_compiler_options(&self, profile: ProfileConfig) -> CompilerOptions {
         CompilerOptions::new(
             self.project_location.clone(),
             self.language.clone(),
-            self.global_include_paths.clone(),
+            Some(self.collect_lists(&self.global_include_paths, |section| &section.global_include_paths)),
             self.global_additional_compiler_args.clone(),
+            profile,
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process};
+
+    #[test]
+    fn saves_only_local_contents_with_added_unit() {
+        let directory = env::temp_dir().join(format!("copper-project-{}", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("base.toml"), r#"
+            language = "c"
+            compiler = "gcc"
+
+            [[Unit]]
+            name = "app"
+            type = "binary"
+            source = "src/app"
+        "#).unwrap();
+        fs::write(directory.join(PROJECT_FILE_NAME), r#"
+            extends = "base.toml"
+            name = "project"
+            language = "C"
+            default-build-directory = "build"
+
+            [[Unit]]
+            name = "tool"
+            type = "bin"
+            source = "src/tool"
+        "#).unwrap();
+
+        let mut project = ProjectConfig::import(&directory).unwrap();
+        project.add_unit("lib".to_string(), UnitType::StaticLibrary, PathBuf::from("src/lib")).unwrap();
+        project.save(&directory).unwrap();
+
+        let saved: toml::Table = toml::from_str(&fs::read_to_string(directory.join(PROJECT_FILE_NAME)).unwrap()).unwrap();
+        let reimported = ProjectConfig::import(&directory);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(saved, toml::from_str(r#"
+            extends = "base.toml"
+            name = "project"
+            language = "C"
+            default-build-directory = "build"
+
+            [[Unit]]
+            name = "tool"
+            type = "bin"
+            source = "src/tool"
+
+            [[Unit]]
+            name = "lib"
+            type = "static-library"
+            source = "src/lib"
+            output-directory = "build/lib"
+            intermediate-directory = "build/obj"
+        "#).unwrap());
+
+        let unit_names: Vec<&str> = reimported.as_ref().unwrap().units.iter().map(|unit| unit.name.as_str()).collect();
+        assert_eq!(unit_names, ["app", "tool", "lib"]);
+    }
+    
+    fn parse_project(contents: &str) -> ProjectConfig {
+        toml::from_str(&format!("name = \"project\"\nlanguage = \"c\"\ncompiler = \"gcc\"\n{}", contents)).unwrap()
+    }
+    
+    fn unit_order(project: &ProjectConfig, unit_names: &[&str]) -> Result<Vec<String>> {
+        let unit_names: Vec<String> = unit_names.iter().map(|name| name.to_string()).collect();
+        let unit_names: Vec<&String> = unit_names.iter().collect();
+        project.resolve_build_order(&unit_names)
+            .map(|order| order.into_iter().map(|unit| unit.name.clone()).collect())
+    }
+    
+    #[test]
+    fn builds_shared_dependency_of_diamond_once_before_its_dependents() {
+        let project = parse_project(r#"
+            [[Unit]]
+            name = "app"
+            type = "binary"
+            source = "src/app"
+            dependencies = ["left", "right"]
+
+            [[Unit]]
+            name = "left"
+            type = "static-library"
+            source = "src/left"
+            dependencies = ["base"]
+
+            [[Unit]]
+            name = "right"
+            type = "static-library"
+            source = "src/right"
+            dependencies = ["base"]
+
+            [[Unit]]
+            name = "base"
+            type = "static-library"
+            source = "src/base"
+        "#);
+        
+        assert_eq!(unit_order(&project, &["app"]).unwrap(), ["base", "left", "right", "app"]);
+        assert_eq!(unit_order(&project, &["right", "app"]).unwrap(), ["base", "right", "left", "app"]);
+    }
+    
+    #[test]
+    fn reports_dependency_cycle_with_its_chain() {
+        let project = parse_project(r#"
+            [[Unit]]
+            name = "app"
+            type = "binary"
+            source = "src/app"
+            dependencies = ["first"]
+
+            [[Unit]]
+            name = "first"
+            type = "static-library"
+            source = "src/first"
+            dependencies = ["second"]
+
+            [[Unit]]
+            name = "second"
+            type = "static-library"
+            source = "src/second"
+            dependencies = ["first"]
+        "#);
+        
+        match unit_order(&project, &["app"]) {
+            Err(Error::DependencyCycle(cycle)) => assert_eq!(cycle, "first -> second -> first"),
+            result => panic!("expected a dependency cycle, got {:?}", result),
+        }
+    }
+    
+    #[test]
+    fn reports_missing_dependency_and_missing_unit() {
+        let project = parse_project(r#"
+            [[Unit]]
+            name = "app"
+            type = "binary"
+            source = "src/app"
+            dependencies = ["missing"]
+        "#);
+        
+        match unit_order(&project, &["app"]) {
+            Err(Error::DependencyNotFound(unit, dependency)) => assert_eq!((unit.as_str(), dependency.as_str()), ("app", "missing")),
+            result => panic!("expected a missing dependency, got {:?}", result),
+        }
+        match unit_order(&project, &["tool"]) {
+            Err(Error::UnitNotFound(unit)) => assert_eq!(unit, "tool"),
+            result => panic!("expected a missing unit, got {:?}", result),
+        }
+    }
+}
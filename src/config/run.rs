@@ -0,0 +1,16 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Configuration of how the binary unit is executed with `copper run`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RunConfig {
+    /// Arguments which are passed to the executable before the ones specified on the command line
+    pub args: Option<Vec<String>>,
+    /// Directory (relative to the project root) which the executable is run in. Defaults to the
+    /// directory the command was executed in
+    pub working_directory: Option<PathBuf>,
+    /// Additional environment variables set for the executable
+    pub env: Option<BTreeMap<String, String>>,
+}
@@ -0,0 +1,268 @@
+//! Conditional configuration sections (`[target.'cfg(...)']`), which extend the project and its
+//! units depending on the host platform and the selected compiler
+
+use std::collections::BTreeMap;
+use std::env::consts;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use super::{Defines, Error, LinkLibrary, ProjectCompiler, Result};
+
+/// Configuration section which is applied only when its condition holds. Lists are appended to
+/// the unconditional ones and definitions override them
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TargetConfig {
+    pub global_include_paths: Option<Vec<PathBuf>>,
+    pub defines: Option<Defines>,
+    pub link_libraries: Option<Vec<LinkLibrary>>,
+    pub library_paths: Option<Vec<PathBuf>>,
+    pub linker_args: Option<Vec<String>>,
+    /// Additions to the units (by unit name)
+    #[serde(rename = "Unit")]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub units: BTreeMap<String, TargetUnitConfig>,
+}
+
+/// Conditional additions to a unit's configuration
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TargetUnitConfig {
+    /// Glob patterns of source files which are added to the unit's source files
+    pub sources: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub include_paths: Option<Vec<PathBuf>>,
+    pub defines: Option<Defines>,
+    pub link_libraries: Option<Vec<LinkLibrary>>,
+    pub library_paths: Option<Vec<PathBuf>>,
+    pub linker_args: Option<Vec<String>>,
+    pub pkg_config: Option<Vec<String>>,
+}
+
+/// Condition of the configuration section: `cfg(<predicate>)`, where the predicate is `unix`,
+/// `windows`, `key = "value"` (with the `target_os`, `target_family`, `target_arch` and `compiler`
+/// keys) or a combination of predicates with `all(...)`, `any(...)` and `not(...)`. Conditions are
+/// evaluated for the host platform
+#[derive(Debug, PartialEq)]
+pub enum Condition {
+    Name(String),
+    KeyValue(String, String),
+    Compiler(ProjectCompiler),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    const NAMES: [&'static str; 2] = ["unix", "windows"];
+    const KEYS: [&'static str; 4] = ["target_os", "target_family", "target_arch", "compiler"];
+
+    /// Parses the `cfg(...)` condition
+    pub fn parse(condition: &str) -> Result<Self> {
+        let invalid = |reason: String| Error::InvalidCondition(condition.to_string(), reason);
+        
+        let mut parser = Parser {
+            tokens: tokenize(condition).map_err(invalid)?,
+            position: 0,
+        };
+        parser.parse_cfg().map_err(invalid)
+    }
+
+    /// Returns whether the condition holds for the host platform and the selected compiler
+    pub fn evaluate(&self, compiler: &ProjectCompiler) -> bool {
+        match self {
+            Condition::Name(name) => name == consts::FAMILY,
+            Condition::KeyValue(key, value) => match key.as_str() {
+                "target_os" => value == consts::OS,
+                "target_family" => value == consts::FAMILY,
+                _ => value == consts::ARCH,
+            },
+            Condition::Compiler(value) => value == compiler,
+            Condition::All(conditions) => conditions.iter().all(|condition| condition.evaluate(compiler)),
+            Condition::Any(conditions) => conditions.iter().any(|condition| condition.evaluate(compiler)),
+            Condition::Not(condition) => !condition.evaluate(compiler),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    String(String),
+    OpenParen,
+    CloseParen,
+    Comma,
+    Equals,
+}
+
+fn tokenize(condition: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = condition.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            ',' => Token::Comma,
+            '=' => Token::Equals,
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                Token::String(value)
+            },
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut identifier = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    identifier.push(c);
+                }
+                Token::Identifier(identifier)
+            },
+            c => return Err(format!("unexpected character '{}'", c)),
+        };
+        tokens.push(token);
+    }
+    
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+    
+    fn expect(&mut self, expected: Token, description: &str) -> std::result::Result<(), String> {
+        match self.next() {
+            Some(token) if *token == expected => Ok(()),
+            _ => Err(format!("expected {}", description)),
+        }
+    }
+    
+    fn parse_cfg(&mut self) -> std::result::Result<Condition, String> {
+        self.expect(Token::Identifier("cfg".to_string()), "'cfg(...)'")?;
+        self.expect(Token::OpenParen, "'(' after 'cfg'")?;
+        let condition = self.parse_predicate()?;
+        self.expect(Token::CloseParen, "')'")?;
+        
+        match self.next() {
+            None => Ok(condition),
+            Some(_) => Err("unexpected input after 'cfg(...)'".to_string()),
+        }
+    }
+    
+    fn parse_predicate(&mut self) -> std::result::Result<Condition, String> {
+        let name = match self.next() {
+            Some(Token::Identifier(name)) => name.clone(),
+            _ => return Err("expected a predicate".to_string()),
+        };
+        
+        match self.tokens.get(self.position) {
+            Some(Token::OpenParen) => {
+                self.position += 1;
+                let mut conditions = Vec::new();
+                while self.tokens.get(self.position) != Some(&Token::CloseParen) {
+                    conditions.push(self.parse_predicate()?);
+                    if self.tokens.get(self.position) == Some(&Token::Comma) {
+                        self.position += 1;
+                    } else {
+                        break;
+                    }
+                }
+                self.expect(Token::CloseParen, "')'")?;
+                
+                match name.as_str() {
+                    "all" => Ok(Condition::All(conditions)),
+                    "any" => Ok(Condition::Any(conditions)),
+                    "not" if conditions.len() == 1 => Ok(Condition::Not(Box::new(conditions.remove(0)))),
+                    "not" => Err("'not' requires exactly one predicate".to_string()),
+                    _ => Err(format!("unknown operator '{}'", name)),
+                }
+            },
+            Some(Token::Equals) => {
+                self.position += 1;
+                let value = match self.next() {
+                    Some(Token::String(value)) => value.clone(),
+                    _ => return Err(format!("expected a quoted value for '{}'", name)),
+                };
+                
+                match name.as_str() {
+                    "compiler" => ProjectCompiler::try_from(value.clone())
+                        .map(Condition::Compiler)
+                        .map_err(|_| format!("unknown compiler '{}' (expected one of {})", value, ProjectCompiler::str_variants().join(", "))),
+                    _ if Condition::KEYS.contains(&name.as_str()) => Ok(Condition::KeyValue(name, value)),
+                    _ => Err(format!("unknown key '{}' (expected one of {})", name, Condition::KEYS.join(", "))),
+                }
+            },
+            _ if Condition::NAMES.contains(&name.as_str()) => Ok(Condition::Name(name)),
+            _ => Err(format!("unknown predicate '{}' (expected one of {})", name, Condition::NAMES.join(", "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_keys() {
+        assert_eq!(Condition::parse("cfg(unix)").unwrap(), Condition::Name("unix".to_string()));
+        assert_eq!(
+            Condition::parse(r#"cfg(target_os = "linux")"#).unwrap(),
+            Condition::KeyValue("target_os".to_string(), "linux".to_string()),
+        );
+        assert_eq!(Condition::parse(r#"cfg(compiler = "gpp")"#).unwrap(), Condition::Compiler(ProjectCompiler::GPP));
+    }
+
+    #[test]
+    fn parses_nested_operators() {
+        let condition = Condition::parse(r#"cfg(all(unix, not(target_arch = "arm"), any(windows, compiler = "gcc")))"#).unwrap();
+        assert_eq!(condition, Condition::All(vec![
+            Condition::Name("unix".to_string()),
+            Condition::Not(Box::new(Condition::KeyValue("target_arch".to_string(), "arm".to_string()))),
+            Condition::Any(vec![
+                Condition::Name("windows".to_string()),
+                Condition::Compiler(ProjectCompiler::GCC),
+            ]),
+        ]));
+    }
+
+    #[test]
+    fn rejects_invalid_conditions() {
+        for condition in [
+            "unix",
+            "cfg(unix",
+            "cfg(unix) extra",
+            "cfg(linux)",
+            r#"cfg(target_vendor = "apple")"#,
+            r#"cfg(compiler = "tcc")"#,
+            "cfg(target_os = linux)",
+            r#"cfg(target_os = "linux)"#,
+            "cfg(not(unix, windows))",
+            "cfg(one(unix))",
+        ] {
+            assert!(
+                matches!(Condition::parse(condition), Err(Error::InvalidCondition(..))),
+                "'{}' should be rejected", condition,
+            );
+        }
+    }
+
+    #[test]
+    fn evaluates_compiler() {
+        let condition = Condition::parse(r#"cfg(compiler = "clang")"#).unwrap();
+        assert!(condition.evaluate(&ProjectCompiler::CLANG));
+        assert!(!condition.evaluate(&ProjectCompiler::GCC));
+    }
+}
@@ -1,10 +1,17 @@
+use std::collections::HashSet;
 use std::ffi::OsString;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
-use std::{fs, io, process};
+use std::process::Command;
+use std::{fs, io};
 use serde::{Deserialize, Serialize};
 use crate::compiler::TargetInformation;
-use super::{ProjectConfig, Error};
+use crate::error::Context;
+use crate::file;
+use super::{ProjectConfig, ProjectLanguage, Error, Defines, LinkLibrary, RunConfig, render_defines, INSTALL_LIBRARY_DIRECTORY_NAME};
+use super::glob::Glob;
+use super::target::TargetUnitConfig;
+use super::pkg_config::{self, PackageFlags};
 
 /// Configuration for the project unit
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +23,15 @@ pub struct UnitConfig {
     pub r#type: UnitType,
     /// Location of the unit within the project (source code files)
     source: PathBuf,
+    /// Language all of the unit's source files are compiled as. The language of each source file
+    /// is detected by its extension if not specified
+    language: Option<ProjectLanguage>,
+    /// Glob patterns (relative to the unit source directory) selecting the unit's source files.
+    /// All files with the language's source extensions are selected if not specified
+    sources: Option<Vec<String>>,
+    /// Glob patterns (relative to the unit source directory) of files and directories which are
+    /// excluded from the unit's source files
+    exclude: Option<Vec<String>>,
     /// Unit's build output location. Will convert `None` into a path generated from the
     /// default project path
     output_directory: Option<PathBuf>,
@@ -24,8 +40,43 @@ pub struct UnitConfig {
     intermediate_directory: Option<PathBuf>,
     /// Pre-unit additional include paths
     include_paths: Option<Vec<PathBuf>>,
+    /// Include paths which are used by the unit itself and by all the units depending on it
+    public_include_paths: Option<Vec<PathBuf>>,
+    /// Header files and directories (relative to the project root) which are installed along
+    /// with the library unit. Contents of the directories are installed recursively
+    public_headers: Option<Vec<PathBuf>>,
+    /// Preprocessor definitions which are used by the unit itself and by all the units depending
+    /// on it
+    public_defines: Option<Defines>,
+    /// Preprocessor definitions which are used only by the unit itself (override the project's
+    /// and the public ones)
+    defines: Option<Defines>,
+    /// Names of the units this unit depends on. Dependencies are built before the unit and
+    /// library dependencies are linked into it
+    dependencies: Option<Vec<String>>,
     /// Per-unit additional compiler arguments
     additional_compiler_args: Option<String>,
+    /// Libraries linked into the unit: names of the libraries searched in the library paths (e.g.
+    /// "m") or paths of library files relative to the project root (e.g. "third_party/libfoo.a").
+    /// Libraries of a static library unit are linked into the units which link the static library
+    link_libraries: Option<Vec<LinkLibrary>>,
+    /// Additional library search paths (relative to the project root)
+    library_paths: Option<Vec<PathBuf>>,
+    /// Arguments passed only to the linker when linking the unit
+    linker_args: Option<Vec<String>>,
+    /// System packages (optionally with version constraints, e.g. "libpng >= 1.6") whose compiler
+    /// and linker flags are queried from pkg-config
+    pkg_config: Option<Vec<String>>,
+    /// Whether the static library unit is linked as a whole into the units depending on it, keeping
+    /// the objects which are not referenced (e.g. plugins registering themselves)
+    link_whole_archive: Option<bool>,
+    /// Version of the dynamic library unit (e.g. "1.2.3"), which is appended to the library's file
+    /// name. The major version is used for the library's soname
+    version: Option<String>,
+    /// Configuration of how the binary unit is executed with `copper run`
+    pub run: Option<RunConfig>,
+    /// Maximum time in seconds the test unit is allowed to run before it is considered failed
+    pub timeout: Option<u64>,
 }
 
 impl UnitConfig {
@@ -42,94 +93,426 @@ impl UnitConfig {
             name,
             r#type,
             source,
+            language: None,
+            sources: None,
+            exclude: None,
             output_directory: Some(output_directory),
             intermediate_directory: Some(intermediate_directory),
             include_paths,
+            public_include_paths: None,
+            public_headers: None,
+            public_defines: None,
+            defines: None,
+            dependencies: None,
             additional_compiler_args,
+            link_libraries: None,
+            library_paths: None,
+            linker_args: None,
+            link_whole_archive: None,
+            pkg_config: None,
+            version: None,
+            run: None,
+            timeout: None,
         }
     }
     
     /// Collects needed information about the unit and returns target information for later usage
-    /// with a compiler
-    pub fn get_target_information(&self, parent_project: &ProjectConfig) -> Option<TargetInformation> {
+    /// with a compiler. Output and intermediate files of each build profile are kept separately.
+    /// Returns `None` if the unit has no source files to build
+    pub fn get_target_information(&self, parent_project: &ProjectConfig, profile_name: &str) -> crate::error::Result<Option<TargetInformation>> {
         let unit_path = parent_project.project_location.join(&self.source);
         
-        let mut source_file_paths = Vec::new();
-        if let Err(err) = self.get_source_files(&mut source_file_paths, unit_path, &parent_project.language.extensions()) {
-            eprintln!("Unable to get unit's source files: {}", err.to_string());
-            process::exit(1);
-        }
+        let source_files = self.get_sources(parent_project)?;
 
-        if source_file_paths.is_empty() {
-            eprintln!("There are no source files to build");
-            return None;
+        if source_files.is_empty() {
+            return Ok(None);
         }
 
         // Output and intermediate directories should be passed as relative to where the project is
         // located
-        let output_directory = {
-            let dir = match &self.output_directory {
-                Some(dir) => dir,
-                None => &self.generate_output_directory(parent_project),
-            };
-            parent_project.project_location.join(dir)
+        let output_directory = self.resolve_output_directory(parent_project, profile_name);
+        
+        let intermediate_directory = self.resolve_intermediate_directory(parent_project, profile_name);
+        
+        let output_file = output_directory.join(self.output_file_name());
+        
+        let library_symlinks = self.get_library_symlinks(&output_directory);
+        
+        let mut dependencies = parent_project.resolve_build_order(&[&self.name])?;
+        // The unit itself is always the last one in its build order
+        dependencies.pop();
+        
+        // Definitions are merged so that the more specific ones override the more general ones:
+        // project, build profile, public definitions of the dependencies and of the unit itself
+        // and finally the unit's own definitions
+        let mut defines = parent_project.get_defines();
+        defines.extend(parent_project.get_profile(profile_name)?.defines.unwrap_or_default());
+        
+        let packages = self.get_package_flags(parent_project)?;
+        defines.extend(packages.defines);
+        
+        let sections = parent_project.get_target_units(&self.name);
+        
+        // Public include paths and definitions of all the dependencies are propagated to the unit
+        let mut include_paths = collect_lists(&self.include_paths, &sections, |section| &section.include_paths);
+        include_paths.extend(packages.include_paths);
+        for unit in dependencies.iter().chain([&self]) {
+            if let Some(paths) = &unit.public_include_paths {
+                include_paths.extend(paths.iter().cloned());
+            }
+            if let Some(unit_defines) = &unit.public_defines {
+                defines.extend(unit_defines.clone());
+            }
+        }
+        defines.extend(self.defines.clone().unwrap_or_default());
+        for section in &sections {
+            defines.extend(section.defines.clone().unwrap_or_default());
+        }
+        
+        let linked_units = match self.r#type {
+            UnitType::StaticLibrary => Vec::new(),
+            _ => self.get_linked_libraries(parent_project, &dependencies),
         };
+        let link_libraries: Vec<PathBuf> = linked_units.iter()
+            .map(|unit| unit.resolve_output_directory(parent_project, profile_name).join(unit.output_file_name()))
+            .collect();
+        let whole_archives = linked_units.iter()
+            .zip(&link_libraries)
+            .filter(|(unit, _)| matches!(unit.r#type, UnitType::StaticLibrary) && unit.link_whole_archive.unwrap_or(false))
+            .map(|(_, file)| file.clone())
+            .collect();
+        
+        // Static libraries linked into the unit need their libraries linked as well. The unit's own
+        // libraries and library paths come first, followed by the ones of the static libraries and
+        // finally the project's
+        let mut library_paths = self.get_library_paths(parent_project);
+        let mut external_libraries = self.get_external_libraries(parent_project);
+        let mut package_linker_args = Vec::new();
+        library_paths.extend(packages.library_paths);
+        external_libraries.extend(packages.link_libraries);
+        package_linker_args.extend(packages.linker_args);
+        
+        let linked_static_libraries = linked_units.iter()
+            .filter(|unit| matches!(unit.r#type, UnitType::StaticLibrary));
+        for unit in linked_static_libraries {
+            let unit_packages = unit.get_package_flags(parent_project)?;
+            library_paths.extend(unit.get_library_paths(parent_project));
+            library_paths.extend(unit_packages.library_paths);
+            external_libraries.extend(unit.get_external_libraries(parent_project));
+            external_libraries.extend(unit_packages.link_libraries);
+            package_linker_args.extend(unit_packages.linker_args);
+        }
+        library_paths.extend(parent_project.get_library_paths());
+        external_libraries.extend(parent_project.get_link_libraries());
+        let external_libraries = external_libraries.into_iter()
+            .map(|library| match library {
+                LinkLibrary::File(file) => LinkLibrary::File(parent_project.project_location.join(file)),
+                library => library,
+            })
+            .collect();
+        
+        let linker_args = parent_project.get_linker_args().into_iter()
+            .chain(collect_lists(&self.linker_args, &sections, |section| &section.linker_args))
+            .chain(package_linker_args)
+            .collect();
+        
+        let additional_args = packages.compile_args.into_iter()
+            .chain(self.additional_compiler_args.iter().flat_map(|args| args.split_whitespace().map(String::from)))
+            .collect();
         
-        let intermediate_directory = {
-            let dir = match &self.intermediate_directory {
-                Some(dir) => dir,
-                None => &self.generate_intermediate_directory(parent_project)
-            };
-            parent_project.project_location.join(dir)
+        // Objects of the static libraries end up in the linked target as well, so the C++ driver
+        // is needed if any of them are compiled as C++
+        let is_cpp = |sources: &[(PathBuf, ProjectLanguage)]| sources.iter()
+            .any(|(_, language)| *language == ProjectLanguage::CPP);
+        let mut link_language = if is_cpp(&source_files) { ProjectLanguage::CPP } else { ProjectLanguage::C };
+        for unit in linked_units.iter().filter(|unit| matches!(unit.r#type, UnitType::StaticLibrary)) {
+            if link_language == ProjectLanguage::CPP {
+                break;
+            }
+            if is_cpp(&unit.get_sources(parent_project)?) {
+                link_language = ProjectLanguage::CPP;
+            }
+        }
+        
+        // Linked targets are able to find their dynamic library dependencies relative to their own
+        // location when run from the build tree. Installed targets are relinked to look for them
+        // under the same prefix instead (binaries go to `bin`, libraries to `lib`)
+        let mut runtime_library_directories = Vec::new();
+        let mut installed_runtime_library_directories = Vec::new();
+        let has_dynamic_libraries = dependencies.iter()
+            .any(|unit| matches!(unit.r#type, UnitType::DynamicLibrary));
+        if has_dynamic_libraries && !matches!(self.r#type, UnitType::StaticLibrary) {
+            dependencies.iter()
+                .filter(|unit| matches!(unit.r#type, UnitType::DynamicLibrary))
+                .map(|unit| file::relative_path(&output_directory, &unit.resolve_output_directory(parent_project, profile_name)))
+                .for_each(|dir| if !runtime_library_directories.contains(&dir) {
+                    runtime_library_directories.push(dir)
+                });
+            match self.r#type {
+                UnitType::Binary => installed_runtime_library_directories.push(Path::new("..").join(INSTALL_LIBRARY_DIRECTORY_NAME)),
+                UnitType::DynamicLibrary => installed_runtime_library_directories.push(PathBuf::new()),
+                _ => {},
+            }
+        }
+        
+        // Static libraries which end up inside of a dynamic library have to be position-independent
+        // as well
+        let position_independent = match self.r#type {
+            UnitType::DynamicLibrary => true,
+            UnitType::StaticLibrary => parent_project.get_dependent_units(&self.name).iter()
+                .any(|unit| matches!(unit.r#type, UnitType::DynamicLibrary)),
+            UnitType::Binary | UnitType::Test => false,
         };
         
-        if let Err(err) = fs::create_dir_all(&output_directory) {
-            if err.kind() != io::ErrorKind::AlreadyExists {
-                eprintln!("Unable to create unit's output directory: {}", err.to_string());
-                process::exit(1);
+        Ok(Some(TargetInformation::new(
+            self.name.clone(),
+            self.r#type.clone(),
+            unit_path,
+            source_files,
+            output_file,
+            intermediate_directory,
+            Some(include_paths),
+            render_defines(&defines),
+            additional_args,
+            link_libraries,
+            whole_archives,
+            library_paths,
+            external_libraries,
+            linker_args,
+            link_language,
+            position_independent,
+            self.soname(),
+            library_symlinks,
+            runtime_library_directories,
+            installed_runtime_library_directories,
+        )))
+    }
+    
+    /// Returns the compiler and linker flags of the unit's pkg-config packages
+    fn get_package_flags(&self, parent_project: &ProjectConfig) -> crate::error::Result<PackageFlags> {
+        let sections = parent_project.get_target_units(&self.name);
+        let packages = collect_lists(&self.pkg_config, &sections, |section| &section.pkg_config);
+        
+        pkg_config::query(&packages)
+            .with_context(|| format!("querying pkg-config for the packages of unit '{}'", self.name))
+    }
+    
+    /// Returns the unit's library search paths (including the ones of the conditional sections)
+    fn get_library_paths(&self, parent_project: &ProjectConfig) -> Vec<PathBuf> {
+        let sections = parent_project.get_target_units(&self.name);
+        collect_lists(&self.library_paths, &sections, |section| &section.library_paths)
+    }
+    
+    /// Returns the libraries linked into the unit (including the ones of the conditional sections)
+    fn get_external_libraries(&self, parent_project: &ProjectConfig) -> Vec<LinkLibrary> {
+        let sections = parent_project.get_target_units(&self.name);
+        collect_lists(&self.link_libraries, &sections, |section| &section.link_libraries)
+    }
+    
+    /// Returns the path of the unit's output file produced with the build profile
+    pub fn get_output_file(&self, parent_project: &ProjectConfig, profile_name: &str) -> PathBuf {
+        self.resolve_output_directory(parent_project, profile_name).join(self.output_file_name())
+    }
+    
+    /// Returns the command which executes the unit's executable with the unit's run configuration:
+    /// its arguments, working directory (relative to the project root) and environment variables
+    pub fn get_run_command(&self, parent_project: &ProjectConfig, executable: &Path) -> Command {
+        let mut command = Command::new(executable);
+        if let Some(run) = &self.run {
+            command.args(run.args.iter().flatten());
+            if let Some(dir) = &run.working_directory {
+                command.current_dir(parent_project.project_location.join(dir));
+            }
+            command.envs(run.env.iter().flatten());
+        }
+        command
+    }
+    
+    /// Returns the header files and directories which are installed along with the unit
+    pub fn get_public_headers(&self) -> &[PathBuf] {
+        self.public_headers.as_deref().unwrap_or_default()
+    }
+    
+    /// Returns the paths of all the build artifacts Copper produces for the unit with the build
+    /// profile: the unit's intermediate directory, its output file and the library symlinks
+    pub fn get_build_artifacts(&self, parent_project: &ProjectConfig, profile_name: &str) -> Vec<PathBuf> {
+        let output_directory = self.resolve_output_directory(parent_project, profile_name);
+        
+        let mut artifacts = vec![
+            self.resolve_intermediate_directory(parent_project, profile_name),
+            output_directory.join(self.output_file_name()),
+        ];
+        artifacts.extend(self.get_library_symlinks(&output_directory).into_iter().map(|(link, _)| link));
+        
+        artifacts
+    }
+    
+    /// Returns the names of the units this unit directly depends on
+    pub fn get_dependencies(&self) -> &[String] {
+        self.dependencies.as_deref().unwrap_or_default()
+    }
+    
+    /// Returns the library units which have to be linked into the unit, ordered so that every
+    /// library precedes the libraries it depends on. Static libraries bring their own library
+    /// dependencies with them, while dynamic libraries are already linked against theirs.
+    /// `dependencies` must contain all the unit's dependencies in the build order
+    fn get_linked_libraries<'a>(&self, parent_project: &'a ProjectConfig, dependencies: &[&'a UnitConfig]) -> Vec<&'a UnitConfig> {
+        let mut linked: Vec<&str> = Vec::new();
+        let mut pending: Vec<&str> = self.get_dependencies().iter().map(String::as_str).collect();
+        
+        while let Some(name) = pending.pop() {
+            let Some(unit) = parent_project.find_unit(name) else { continue };
+            
+            if matches!(unit.r#type, UnitType::Binary | UnitType::Test) || linked.contains(&name) {
+                continue;
+            }
+            linked.push(name);
+            
+            if let UnitType::StaticLibrary = unit.r#type {
+                pending.extend(unit.get_dependencies().iter().map(String::as_str));
             }
         }
-
-        let mut output_file = output_directory.join(&self.name);
+        
+        // Reversed build order places dependent units before their dependencies
+        dependencies.iter()
+            .rev()
+            .filter(|unit| linked.contains(&unit.name.as_str()))
+            .copied()
+            .collect()
+    }
+    
+    /// Returns the output directory of the unit for the build profile relative to where the
+    /// command was executed
+    fn resolve_output_directory(&self, parent_project: &ProjectConfig, profile_name: &str) -> PathBuf {
+        let dir = match &self.output_directory {
+            Some(dir) => dir,
+            None => &self.generate_output_directory(parent_project),
+        };
+        parent_project.project_location.join(dir).join(profile_name)
+    }
+    
+    /// Returns the intermediate directory of the unit for the build profile relative to where the
+    /// command was executed. Intermediate directory may be shared between multiple units, so every
+    /// unit keeps its object files in a separate subdirectory
+    fn resolve_intermediate_directory(&self, parent_project: &ProjectConfig, profile_name: &str) -> PathBuf {
+        let dir = match &self.intermediate_directory {
+            Some(dir) => dir,
+            None => &self.generate_intermediate_directory(parent_project)
+        };
+        parent_project.project_location.join(dir).join(profile_name).join(&self.name)
+    }
+    
+    /// Returns the symbolic links (link path in the directory and its target) which accompany the
+    /// versioned dynamic library unit: the soname and the unversioned linker name links, which
+    /// point to the previous name in the chain
+    pub fn get_library_symlinks(&self, output_directory: &Path) -> Vec<(PathBuf, PathBuf)> {
+        let mut library_symlinks = Vec::new();
+        
+        if let (UnitType::DynamicLibrary, Some(soname)) = (&self.r#type, self.soname()) {
+            let linker_name = self.linker_name();
+            let file_name = self.output_file_name();
+            
+            if soname != file_name {
+                library_symlinks.push((output_directory.join(&soname), PathBuf::from(&file_name)));
+            }
+            if linker_name != soname {
+                library_symlinks.push((output_directory.join(&linker_name), PathBuf::from(&soname)));
+            }
+        }
+        
+        library_symlinks
+    }
+    
+    /// Returns the file name of the unit's output file according to its type and the current
+    /// platform
+    fn output_file_name(&self) -> String {
         match self.r#type {
-            UnitType::Binary => {
+            UnitType::Binary | UnitType::Test => {
                 if cfg!(windows) {
-                    output_file.set_extension("exe");
+                    format!("{}.exe", self.name)
+                } else {
+                    self.name.clone()
                 }
             },
-            _ => unimplemented!()
+            UnitType::StaticLibrary => format!("lib{}.a", self.name),
+            UnitType::DynamicLibrary => match &self.version {
+                Some(version) if !cfg!(any(windows, target_os = "macos")) => format!("{}.{}", self.linker_name(), version),
+                _ => self.linker_name(),
+            },
+        }
+    }
+    
+    /// Returns the name which is used to link against the dynamic library (without a version)
+    fn linker_name(&self) -> String {
+        if cfg!(windows) {
+            format!("{}.dll", self.name)
+        } else if cfg!(target_os = "macos") {
+            format!("lib{}.dylib", self.name)
+        } else {
+            format!("lib{}.so", self.name)
+        }
+    }
+    
+    /// Returns the soname of the dynamic library unit, which includes only the major version of
+    /// the library. Returns `None` for other unit types and on Windows
+    fn soname(&self) -> Option<String> {
+        if !matches!(self.r#type, UnitType::DynamicLibrary) || cfg!(windows) {
+            return None;
         }
-
         
-        Some(TargetInformation::new(
-            self.name.clone(),
-            self.r#type.clone(),
-            source_file_paths,
-            output_directory,
-            intermediate_directory,
-            self.include_paths.clone(),
-            self.additional_compiler_args.clone(),
-        ))
+        match &self.version {
+            Some(version) if !cfg!(target_os = "macos") => {
+                let major = version.split('.').next().unwrap_or(version);
+                Some(format!("{}.{}", self.linker_name(), major))
+            },
+            _ => Some(self.linker_name()),
+        }
     }
 
-    /// Recursively searches the directory for the source files by extension (according to the
-    /// language) and appends their paths to the vector of source file paths
-    fn get_source_files(&self, source_paths: &mut Vec<PathBuf>, dir_path: PathBuf, extensions: &Vec<OsString>) -> io::Result<()> {
-        for entry in fs::read_dir(&dir_path)? {
-            let path = entry?.path();
-
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if extensions.contains(&ext.to_os_string()) {
-                        source_paths.push(path);
-                    }
-                }
-            } else {
-                self.get_source_files(source_paths, path, extensions)?;
-            }
-        }
+    /// Returns the unit's source files together with the language each of them is compiled as.
+    /// The unit's language override takes precedence over the language detected by the file's
+    /// extension, and files with unknown extensions are compiled in the project's language
+    fn get_sources(&self, parent_project: &ProjectConfig) -> crate::error::Result<Vec<(PathBuf, ProjectLanguage)>> {
+        let unit_path = parent_project.project_location.join(&self.source);
+        let extensions = self.language.as_ref().unwrap_or(&parent_project.language).extensions();
+        
+        let source_files = self.get_source_files(&unit_path, &extensions, &parent_project.get_target_units(&self.name))
+            .with_context(|| format!("searching for source files in {}", unit_path.display()))?;
+        
+        Ok(source_files.into_iter()
+            .map(|path| {
+                let language = self.language.clone()
+                    .or_else(|| path.extension().and_then(ProjectLanguage::from_extension))
+                    .unwrap_or_else(|| parent_project.language.clone());
+                (path, language)
+            })
+            .collect())
+    }
 
-        Ok(())
+    /// Returns the sorted paths of the unit's source files. Files are selected by the unit's
+    /// `sources` patterns or by extension (according to the language), unless they match the
+    /// unit's `exclude` patterns. Files matching the `sources` patterns of the conditional sections
+    /// are added even if the unit excludes them, while the `exclude` patterns of the conditional
+    /// sections exclude any files
+    fn get_source_files(&self, unit_path: &Path, extensions: &[OsString], sections: &[&TargetUnitConfig]) -> crate::error::Result<Vec<PathBuf>> {
+        let parse = |patterns: &[String]| patterns.iter()
+            .map(|pattern| Glob::new(pattern))
+            .collect::<super::Result<Vec<_>>>();
+        
+        let selection = SourceSelection {
+            sources: self.sources.as_deref().map(parse).transpose()?,
+            extensions,
+            exclude: parse(self.exclude.as_deref().unwrap_or_default())?,
+            section_sources: parse(&collect_lists(&None, sections, |section| &section.sources))?,
+            section_exclude: parse(&collect_lists(&None, sections, |section| &section.exclude))?,
+        };
+        
+        let mut source_paths = Vec::new();
+        let mut visited = HashSet::new();
+        selection.find_files(unit_path, unit_path, false, &mut visited, &mut source_paths)?;
+        
+        source_paths.sort();
+        Ok(source_paths)
     }
 
     /// Generates an output directory based on the project's defaults and self's type
@@ -137,7 +520,7 @@ impl UnitConfig {
         let build_dir = &parent_project.default_build_directory;
 
         match self.r#type {
-            UnitType::Binary => build_dir.join(&parent_project.default_binary_directory),
+            UnitType::Binary | UnitType::Test => build_dir.join(&parent_project.default_binary_directory),
             UnitType::StaticLibrary | UnitType::DynamicLibrary => build_dir.join(&parent_project.default_library_directory)
         }
     }
@@ -148,6 +531,80 @@ impl UnitConfig {
     }
 }
 
+/// Glob patterns and extensions selecting the unit's source files
+struct SourceSelection<'a> {
+    /// The unit's `sources` patterns (files are selected by extension if not specified)
+    sources: Option<Vec<Glob>>,
+    extensions: &'a [OsString],
+    exclude: Vec<Glob>,
+    section_sources: Vec<Glob>,
+    section_exclude: Vec<Glob>,
+}
+
+impl SourceSelection<'_> {
+    /// Recursively searches the directory for the source files. `excluded` tells whether the
+    /// directory is excluded by the unit's own patterns, in which case only the conditional
+    /// sources are searched in it. Every directory is visited only once (by its canonical path),
+    /// so symbolic links forming a loop are not followed again
+    fn find_files(
+        &self,
+        root: &Path,
+        dir_path: &Path,
+        excluded: bool,
+        visited: &mut HashSet<PathBuf>,
+        source_paths: &mut Vec<PathBuf>,
+    ) -> io::Result<()> {
+        if !visited.insert(dir_path.canonicalize()?) {
+            return Ok(());
+        }
+        
+        for entry in fs::read_dir(dir_path)? {
+            let path = entry?.path();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            
+            if matches_any(&self.section_exclude, relative) {
+                continue;
+            }
+            let excluded = excluded || matches_any(&self.exclude, relative);
+            
+            if path.is_dir() {
+                if !excluded || !self.section_sources.is_empty() {
+                    self.find_files(root, &path, excluded, visited, source_paths)?;
+                }
+            } else if path.is_file() && self.is_source(relative, excluded) {
+                source_paths.push(path);
+            }
+        }
+
+        Ok(())
+    }
+    
+    fn is_source(&self, relative: &Path, excluded: bool) -> bool {
+        if matches_any(&self.section_sources, relative) {
+            return true;
+        }
+        
+        !excluded && match &self.sources {
+            Some(sources) => matches_any(sources, relative),
+            None => relative.extension().is_some_and(|ext| self.extensions.iter().any(|e| e == ext)),
+        }
+    }
+}
+
+fn matches_any(globs: &[Glob], path: &Path) -> bool {
+    globs.iter().any(|glob| glob.matches(path))
+}
+
+/// Returns the unit's list followed by the lists of the conditional sections
+fn collect_lists<T: Clone>(list: &Option<Vec<T>>, sections: &[&TargetUnitConfig], field: impl Fn(&TargetUnitConfig) -> &Option<Vec<T>>) -> Vec<T> {
+    std::iter::once(list)
+        .chain(sections.iter().map(|section| field(section)))
+        .flatten()
+        .flatten()
+        .cloned()
+        .collect()
+}
+
 /// Enum representing available project languages
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(try_from = "String", into = "String")]
@@ -155,16 +612,20 @@ pub enum UnitType {
     Binary,
     StaticLibrary,
     DynamicLibrary,
+    /// Test executable, which is built only for `copper test` and considered passed if it exits
+    /// successfully
+    Test,
 }
 
 impl UnitType {
     const BINARY_STR: &'static str = "binary";
     const STATIC_LIBRARY_STR: &'static str = "static-library";
     const DYNAMIC_LIBRARY_STR: &'static str = "dynamic-library";
+    const TEST_STR: &'static str = "test";
 
     /// Returns an array of possible unit type variants as stings
-    pub fn str_variants() -> [&'static str; 3] {
-        [Self::BINARY_STR, Self::STATIC_LIBRARY_STR, Self::DYNAMIC_LIBRARY_STR]
+    pub fn str_variants() -> [&'static str; 4] {
+        [Self::BINARY_STR, Self::STATIC_LIBRARY_STR, Self::DYNAMIC_LIBRARY_STR, Self::TEST_STR]
     }
 }
 
@@ -174,6 +635,7 @@ impl Display for UnitType {
             UnitType::Binary => Self::BINARY_STR,
             UnitType::StaticLibrary => Self::STATIC_LIBRARY_STR,
             UnitType::DynamicLibrary => Self::DYNAMIC_LIBRARY_STR,
+            UnitType::Test => Self::TEST_STR,
         })
     }
 }
@@ -186,17 +648,71 @@ impl TryFrom<String> for UnitType {
             Self::BINARY_STR | "bin" => Ok(UnitType::Binary),
             Self::STATIC_LIBRARY_STR | "static-lib" => Ok(UnitType::StaticLibrary),
             Self::DYNAMIC_LIBRARY_STR | "dynamic-lib" => Ok(UnitType::DynamicLibrary),
+            Self::TEST_STR => Ok(UnitType::Test),
             _ => Err(Error::InvalidUnitType(value)),
         }
     }
 }
 
-impl Into<String> for UnitType {
-    fn into(self) -> String {
-        match self {
-            UnitType::Binary => Self::BINARY_STR.to_string(),
-            UnitType::StaticLibrary => Self::STATIC_LIBRARY_STR.to_string(),
-            UnitType::DynamicLibrary => Self::DYNAMIC_LIBRARY_STR.to_string(),
+impl From<UnitType> for String {
+    fn from(value: UnitType) -> Self {
+        match value {
+            UnitType::Binary => UnitType::BINARY_STR.to_string(),
+            UnitType::StaticLibrary => UnitType::STATIC_LIBRARY_STR.to_string(),
+            UnitType::DynamicLibrary => UnitType::DYNAMIC_LIBRARY_STR.to_string(),
+            UnitType::Test => UnitType::TEST_STR.to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_project(contents: &str) -> ProjectConfig {
+        toml::from_str(&format!("name = \"project\"\nlanguage = \"c\"\ncompiler = \"gcc\"\n{}", contents)).unwrap()
+    }
+
+    #[test]
+    fn links_static_library_dependencies_through_to_binary() {
+        let project = parse_project(r#"
+            [[Unit]]
+            name = "app"
+            type = "binary"
+            source = "src/app"
+            dependencies = ["util", "shared"]
+
+            [[Unit]]
+            name = "util"
+            type = "static-library"
+            source = "src/util"
+            dependencies = ["base"]
+
+            [[Unit]]
+            name = "base"
+            type = "static-library"
+            source = "src/base"
+
+            [[Unit]]
+            name = "shared"
+            type = "dynamic-library"
+            source = "src/shared"
+            dependencies = ["core"]
+
+            [[Unit]]
+            name = "core"
+            type = "static-library"
+            source = "src/core"
+        "#);
+        let app = project.find_unit("app").unwrap();
+        let mut dependencies = project.resolve_build_order(&[&app.name]).unwrap();
+        dependencies.pop();
+
+        let linked: Vec<&str> = app.get_linked_libraries(&project, &dependencies).iter()
+            .map(|unit| unit.name.as_str())
+            .collect();
+
+        // The dynamic library is already linked against its own static dependency
+        assert_eq!(linked, ["shared", "util", "base"]);
+    }
+}
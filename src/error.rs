@@ -1,44 +1,138 @@
-#![allow(dead_code)]
+//! Contains the main error type of the program, into which all the module-specific errors are
+//! converted and bubbled up to `main`
+
 use std::fmt::{Display, Formatter};
+use std::io;
 use std::process::Output;
+use crate::{compiler, config};
 
 /// Type alias for the custom error type
 pub type Result<T> = std::result::Result<T, Error>;
 
-/// Custom error type
+/// Exit codes which the program terminates with. They are a stable part of the command line
+/// interface and can be relied upon by scripts
+pub mod exit_code {
+    /// General error (e.g. failed to read or write a file)
+    pub const GENERAL: u8 = 1;
+    /// Invalid command line usage (reported by the argument parser)
+    #[allow(dead_code)]
+    pub const USAGE: u8 = 2;
+    /// Invalid or missing project configuration
+    pub const CONFIG: u8 = 3;
+    /// Failed to compile, link or archive the project files
+    pub const BUILD: u8 = 4;
+    /// The compiler toolchain is missing or not supported
+    pub const TOOLCHAIN: u8 = 5;
+    /// One or more tests have failed
+    pub const TEST_FAILURE: u8 = 6;
+}
+
+/// Custom error type. Holds the kind of the error together with the chain of messages describing
+/// what was being done when the error occurred
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    /// Context messages ordered from the innermost to the outermost one
+    context: Vec<String>,
+}
+
+/// Kind of the error, which is determined by the module it originated from
 #[derive(Debug)]
-pub enum Error {
+pub enum ErrorKind {
     /// Error related to configuration of the project
-    ProjectConfigError(String),
-    /// Error related to the Copper project in general
-    ProjectError(String),
-    /// Error related to the Copper unit in general
-    UnitError(String),
-    /// Error related to writing and reading files and directories 
-    IOError(String),
-    /// Error related to being unable to parse enum values
-    EnumParseError(String),
-}
-
-// TODO: add better output for the Output
+    Config(config::Error),
+    /// Error related to compiling, linking and the compiler toolchain
+    Compiler(compiler::Error),
+    /// Error related to writing and reading files and directories
+    IO(io::Error),
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Self {
+        Error {
+            kind,
+            context: Vec::new(),
+        }
+    }
+
+    /// Wraps the error into an additional (outer) context message
+    pub fn context(mut self, message: impl Into<String>) -> Self {
+        self.context.push(message.into());
+        self
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+    
+    /// Returns a single line describing the innermost error without its context
+    pub fn first_line(&self) -> String {
+        match &self.kind {
+            ErrorKind::Compiler(err) => err.first_line(),
+            ErrorKind::Config(err) => err.to_string(),
+            ErrorKind::IO(err) => err.to_string(),
+        }
+    }
+
+    /// Returns the exit code corresponding to the kind of the error
+    pub fn exit_code(&self) -> u8 {
+        match &self.kind {
+            ErrorKind::Config(_) => exit_code::CONFIG,
+            ErrorKind::Compiler(compiler::Error::UnsupportedCompiler(_) | compiler::Error::CompilerNotFound(_)) => exit_code::TOOLCHAIN,
+            ErrorKind::Compiler(_) => exit_code::BUILD,
+            ErrorKind::IO(_) => exit_code::GENERAL,
+        }
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::ProjectConfigError(s) => write!(f, "Configuration error: {}", s),
-            Error::ProjectError(s) => write!(f, "Project error: {}", s),
-            Error::UnitError(s) => write!(f, "Project unit error: {}", s),
-            Error::IOError(s) => write!(f, "IO error: {}", s),
-            Error::EnumParseError(s) => write!(f, "Unable to parse an enum: {}", s),
+        for message in self.context.iter().rev() {
+            write!(f, "{}: ", message)?;
+        }
+
+        match &self.kind {
+            ErrorKind::Config(err) => write!(f, "{}", err),
+            ErrorKind::Compiler(err) => write!(f, "{}", err),
+            ErrorKind::IO(err) => write!(f, "{}", err),
         }
     }
 }
 
-impl From<std::io::Error> for Error {
-    fn from(error: std::io::Error) -> Self {
-        // TODO: match according to error kind for specific errors
-        match error.kind() {
-            _ => Error::IOError(error.to_string()),
-        }
+impl From<config::Error> for Error {
+    fn from(error: config::Error) -> Self {
+        Error::new(ErrorKind::Config(error))
+    }
+}
+
+impl From<compiler::Error> for Error {
+    fn from(error: compiler::Error) -> Self {
+        Error::new(ErrorKind::Compiler(error))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::new(ErrorKind::IO(error))
+    }
+}
+
+/// Extension for results which allows to add context to their errors
+pub trait Context<T> {
+    /// Converts the error and wraps it into the context message
+    fn context(self, message: impl Into<String>) -> Result<T>;
+
+    /// Converts the error and wraps it into the lazily evaluated context message
+    fn with_context<F: FnOnce() -> String>(self, message: F) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+    fn context(self, message: impl Into<String>) -> Result<T> {
+        self.map_err(|err| err.into().context(message))
+    }
+
+    fn with_context<F: FnOnce() -> String>(self, message: F) -> Result<T> {
+        self.map_err(|err| err.into().context(message()))
     }
 }
 
@@ -47,11 +141,11 @@ pub fn parse_output(output: &Output) -> String {
     let mut message = String::new();
     message.push_str(format!("{}", output.status).as_str());
 
-    if output.stdout.len() > 0 {
+    if !output.stdout.is_empty() {
         message.push_str(format!("\nStdout:\n{}", String::from_utf8_lossy(&output.stdout)).as_str());
     }
 
-    if output.stderr.len() > 0 {
+    if !output.stderr.is_empty() {
         message.push_str(format!("\nStderr:\n{}", String::from_utf8_lossy(&output.stderr)).as_str());
     }
 
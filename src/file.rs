@@ -1,8 +1,7 @@
 use std::{fs, io};
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
-#[allow(dead_code)]
 pub fn read_file(path: &Path) -> io::Result<String> {
     let mut file = fs::File::open(path)?;
     let mut data = String::new();
@@ -11,10 +10,40 @@ pub fn read_file(path: &Path) -> io::Result<String> {
     Ok(data)
 }
 
-#[allow(dead_code)]
 pub fn write_file(path: &Path, data: String) -> io::Result<()> {
     let mut file = fs::File::create(path)?;
     file.write_all(data.as_bytes())?;
     file.flush()?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Creates a symbolic link at `link` pointing to `original`
+pub fn create_symlink(original: &Path, link: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    return std::os::unix::fs::symlink(original, link);
+    
+    #[cfg(windows)]
+    return std::os::windows::fs::symlink_file(original, link);
+}
+
+/// Returns the path to `to` relative to the `from` directory. Both paths are compared lexically,
+/// so they should be relative to the same location (or both be absolute)
+pub fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    fn normalize(path: &Path) -> Vec<Component<'_>> {
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect()
+    }
+    let from = normalize(from);
+    let to = normalize(to);
+    
+    let common = from.iter()
+        .zip(&to)
+        .take_while(|(a, b)| a == b)
+        .count();
+    
+    let mut path = PathBuf::new();
+    from[common..].iter().for_each(|_| path.push(".."));
+    to[common..].iter().for_each(|component| path.push(component));
+    path
+}
@@ -1,53 +1,146 @@
 use std::path::Path;
-use std::process;
-use crate::compiler::Compiler;
-use crate::config::{ProjectConfig, Error, Result};
+use crate::compiler::{self, Compiler, Message, MessageFormat};
+use crate::config::{ProjectConfig, Error};
+use crate::error::{self, Context, ErrorKind, Result};
+use crate::output;
+use super::compdb::write_compile_commands;
 
-pub fn build<'a>(unit_names: Option<impl Iterator<Item = &'a String>>, project_location: &Path) {
-    let project = match ProjectConfig::import(project_location) {
-        Ok(project) => project,
-        Err(err) => {
-            eprintln!("Unable to import project: {}", err);
-            process::exit(1);
-        }
-    };
+/// Settings which control how the units are built
+#[derive(Default)]
+pub struct BuildSettings {
+    /// Maximum number of source files compiled at the same time (defaults to the number of CPUs)
+    pub jobs: Option<usize>,
+    pub message_format: MessageFormat,
+    /// Whether to continue building after a failure
+    pub keep_going: bool,
+    /// Whether to only print the commands instead of executing them
+    pub dry_run: bool,
+}
+
+pub fn build<'a>(
+    unit_names: Option<impl Iterator<Item = &'a String>>,
+    project_location: &Path,
+    profile_name: &str,
+    settings: BuildSettings,
+    compile_commands: bool,
+) -> Result<()> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let requested_names: Option<Vec<&String>> = unit_names.map(|names| names.collect());
     
-    if let Err(err) = build_units(&project, unit_names) {
-        println!("Unable to build project");
-        eprintln!("{}", err);
-        process::exit(1);
+    // The compilation database covers the same units as `copper compdb`, including the tests
+    if compile_commands {
+        let database_names = requested_names.clone().unwrap_or_else(|| project.get_unit_names());
+        write_compile_commands(&project, database_names, profile_name)
+            .context("unable to generate compilation database")?;
     }
+    
+    let unit_names = requested_names.unwrap_or_else(|| project.get_default_unit_names());
+    
+    let result = build_units(&project, unit_names, profile_name, &settings)
+        .context("unable to build project");
 
-    println!("Copper project build finished");
+    match settings.message_format {
+        MessageFormat::Human => if result.is_ok() && !settings.dry_run {
+            output::status(format!("Copper project build finished (profile '{}')", profile_name));
+        },
+        MessageFormat::Json => Message::BuildFinished { success: result.is_ok() }.emit(),
+    }
+    result
 }
 
-/// Builds specifies units (by name) or the whole project (all units)
-fn build_units<'a>(project: &ProjectConfig, unit_names: Option<impl Iterator<Item = &'a String>>) -> Result<()> {
-    let unit_names = match unit_names {
-        None => project.get_unit_names(),
-        Some(names) => names.collect(),
-    };
+/// Initializes the project's compiler with the options of the build profile and the settings
+pub(super) fn initialize_compiler(project: &ProjectConfig, profile_name: &str, settings: &BuildSettings) -> Result<Compiler> {
+    let profile = project.get_profile(profile_name)?;
     
+    let mut compiler_options = project.get_compiler_options(profile);
+    if let Some(jobs) = settings.jobs {
+        compiler_options.set_jobs(jobs);
+    }
+    compiler_options.set_message_format(settings.message_format);
+    compiler_options.set_keep_going(settings.keep_going);
+    compiler_options.set_dry_run(settings.dry_run);
+    
+    Compiler::initialize(project.compiler.clone(), compiler_options)
+}
+
+/// Builds specifies units (by name) or the whole project (all units). If the build keeps going,
+/// it continues after a failure, skipping only the units which depend on the failed ones, and
+/// all the failures are summarized at the end
+pub(super) fn build_units(project: &ProjectConfig, unit_names: Vec<&String>, profile_name: &str, settings: &BuildSettings) -> Result<()> {
     if unit_names.is_empty() {
-        return Err(Error::NoUnits)
+        return Err(Error::NoUnits.into())
     }
     
-    let compiler_options = project.get_compiler_options();
-    let compiler = Compiler::initialize(project.compiler.clone(), compiler_options);
+    let units = project.resolve_build_order(&unit_names)?;
+    
+    let compiler = initialize_compiler(project, profile_name, settings)?;
+    
+    let mut failures: Vec<(String, error::Error)> = Vec::new();
+    let mut skipped: Vec<(String, String)> = Vec::new();
+    // Units which failed or were skipped, so their dependents have to be skipped as well
+    let mut failed_units: Vec<&str> = Vec::new();
 
-    for unit_name in unit_names {
+    for unit in units {
+        let failed_dependency = project.resolve_build_order(&[&unit.name])?.into_iter()
+            .find(|dependency| failed_units.contains(&dependency.name.as_str()));
+        if let Some(dependency) = failed_dependency {
+            skipped.push((unit.name.clone(), dependency.name.clone()));
+            failed_units.push(&unit.name);
+            continue;
+        }
         
-        let target = match project.find_unit(unit_name) {
-            Some(unit) => unit.get_target_information(&project),
-            None => return Err(Error::UnitNotFound(unit_name.to_string())),
-        };
+        let result = unit.get_target_information(project, profile_name)
+            .and_then(|target| match target {
+                Some(target) => compiler.build(target),
+                None => {
+                    if settings.message_format == MessageFormat::Human {
+                        output::status(format!("Unit '{}' has no source files to build", unit.name));
+                    }
+                    Ok(())
+                },
+            })
+            .with_context(|| format!("while building unit '{}'", unit.name));
         
-        if let None = target {
-            continue
+        match result {
+            Err(err) if settings.keep_going => {
+                eprintln!("error: {}", err);
+                failures.push((unit.name.clone(), err));
+                failed_units.push(&unit.name);
+            },
+            result => result?,
         }
-
-        compiler.build(target.unwrap());
     }
+    
+    if failures.is_empty() {
+        return Ok(());
+    }
+    
+    print_failure_summary(&failures, &skipped);
+    
+    let failed_units = failures.into_iter().map(|(unit_name, _)| unit_name).collect();
+    Err(compiler::Error::UnitsFailed(failed_units).into())
+}
 
-    Ok(())
-}
\ No newline at end of file
+/// Prints every failed unit with the first error line (which names the file) of each of its failed
+/// source files, followed by the units which were skipped because of their failed dependencies
+fn print_failure_summary(failures: &[(String, error::Error)], skipped: &[(String, String)]) {
+    eprintln!("\nBuild summary: {} units failed, {} units skipped", failures.len(), skipped.len());
+    
+    for (unit_name, err) in failures {
+        match err.kind() {
+            ErrorKind::Compiler(compiler::Error::SourcesFailed(sources)) => {
+                eprintln!("  unit '{}' failed:", unit_name);
+                for (_, line) in sources {
+                    eprintln!("    {}", line);
+                }
+            },
+            _ => eprintln!("  unit '{}' failed: {}", unit_name, err.first_line()),
+        }
+    }
+    
+    for (unit_name, dependency) in skipped {
+        eprintln!("  unit '{}' skipped (depends on failed unit '{}')", unit_name, dependency);
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use crate::config::{ProjectConfig, Error};
+use crate::output;
+use crate::error::{Context, Result};
+
+/// Removes the build artifacts of the specified units (or all units) produced with the specified
+/// build profile (or all profiles). With `dry_run` only lists the files which would be removed
+pub fn clean<'a>(
+    unit_names: Option<impl Iterator<Item = &'a String>>,
+    project_location: &Path,
+    profile_name: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let units = match unit_names {
+        None => project.get_unit_names().into_iter()
+            .filter_map(|name| project.find_unit(name))
+            .collect(),
+        Some(names) => names
+            .map(|name| project.find_unit(name).ok_or(Error::UnitNotFound(name.to_string())))
+            .collect::<std::result::Result<Vec<_>, _>>()?,
+    };
+    
+    let profile_names = match profile_name {
+        Some(name) => {
+            project.get_profile(name)?;
+            vec![name]
+        },
+        None => project.get_profile_names(),
+    };
+    
+    let project_root = project.project_location.canonicalize()
+        .with_context(|| format!("resolving project root {}", project.project_location.display()))?;
+    
+    // Every artifact is checked before anything is removed, so a unit pointing outside of the
+    // project doesn't leave the other units half cleaned
+    let mut artifacts = Vec::new();
+    for unit in units {
+        for profile_name in &profile_names {
+            for artifact in unit.get_build_artifacts(&project, profile_name) {
+                // Nothing to remove if the artifact was never produced
+                if fs::symlink_metadata(&artifact).is_err() {
+                    continue;
+                }
+                
+                let location = resolve_location(&artifact)
+                    .with_context(|| format!("resolving {}", artifact.display()))?;
+                if !location.starts_with(&project_root) || location == project_root {
+                    return Err(Error::PathOutsideProject(artifact.display().to_string()))
+                        .with_context(|| format!("refusing to clean unit '{}'", unit.name));
+                }
+                artifacts.push(artifact);
+            }
+        }
+    }
+    
+    let mut removed = 0;
+    for artifact in artifacts {
+        if dry_run {
+            println!("Would remove '{}'", artifact.display());
+        } else {
+            // The artifact may have been removed together with a previously removed directory
+            let Ok(metadata) = fs::symlink_metadata(&artifact) else { continue };
+            let result = if metadata.is_dir() {
+                fs::remove_dir_all(&artifact)
+            } else {
+                fs::remove_file(&artifact)
+            };
+            result.with_context(|| format!("removing {}", artifact.display()))?;
+            output::status(format!("Removed '{}'", artifact.display()));
+        }
+        removed += 1;
+    }
+    
+    if dry_run {
+        println!("{} build artifacts would be removed", removed);
+    } else {
+        output::status(format!("Removed {} build artifacts", removed));
+    }
+    Ok(())
+}
+
+/// Returns the absolute location of the path without following the path itself if it is a
+/// symbolic link (so the location of the link is checked rather than its target)
+fn resolve_location(path: &Path) -> io::Result<PathBuf> {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => Ok(parent.canonicalize()?.join(name)),
+        _ => path.canonicalize(),
+    }
+}
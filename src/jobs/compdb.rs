@@ -0,0 +1,50 @@
+use std::path::Path;
+use crate::compiler::{self, Compiler};
+use crate::config::{ProjectConfig, Error};
+use crate::output;
+use crate::error::{Context, Result};
+
+/// Generates a compilation database for the specified units (or all units) without building them
+pub fn compdb<'a>(unit_names: Option<impl Iterator<Item = &'a String>>, project_location: &Path, profile_name: &str) -> Result<()> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let unit_names = match unit_names {
+        None => project.get_unit_names(),
+        Some(names) => names.collect(),
+    };
+    
+    write_compile_commands(&project, unit_names, profile_name)
+        .context("unable to generate compilation database")
+}
+
+/// Collects the compile commands of every translation unit of the specified units and writes them
+/// to the `compile_commands.json` file at the project root
+pub fn write_compile_commands(project: &ProjectConfig, unit_names: Vec<&String>, profile_name: &str) -> Result<()> {
+    if unit_names.is_empty() {
+        return Err(Error::NoUnits.into())
+    }
+    
+    let units = project.resolve_build_order(&unit_names)?;
+    let profile = project.get_profile(profile_name)?;
+    let compiler = Compiler::initialize(project.compiler.clone(), project.get_compiler_options(profile))?;
+    
+    let mut commands = Vec::new();
+    for unit in units {
+        let target = unit.get_target_information(project, profile_name)
+            .with_context(|| format!("while processing unit '{}'", unit.name))?;
+        
+        if let Some(target) = target {
+            let unit_commands = compiler.get_compile_commands(&target)
+                .with_context(|| format!("while processing unit '{}'", unit.name))?;
+            commands.extend(unit_commands);
+        }
+    }
+    
+    let database_path = project.project_location.join(compiler::COMPILATION_DATABASE_FILE_NAME);
+    compiler::write_compilation_database(&database_path, &commands)
+        .with_context(|| format!("writing {}", database_path.display()))?;
+    
+    output::status(format!("Generated compilation database with {} entries at '{}'", commands.len(), database_path.display()));
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
-use std::{fs, io, process};
+use std::{fs, io};
 use crate::config::{self, ProjectConfig, ProjectLanguage, ProjectCompiler, UnitConfig, UnitType};
+use crate::output;
+use crate::error::{Context, Result};
 
 /// Initiates a new copper project by generating a copper.toml in the provided project location and
 /// filling in all the required data
@@ -9,7 +11,7 @@ pub fn init(
     project_name: String, 
     project_language: ProjectLanguage, 
     generate_example: bool
-) {
+) -> Result<()> {
     let default_compiler = {
         if cfg!(windows) {
             ProjectCompiler::MSVC
@@ -24,21 +26,15 @@ pub fn init(
     let mut include_paths = None;
     let mut units = Vec::new();
 
-    if !fs::exists(&project_location).unwrap_or(false) {
-        if let Err(err) = fs::create_dir_all(&project_location) {
-            println!("Error creating project directory: {}", err);
-            process::exit(1);
-        }
+    if !fs::exists(project_location).unwrap_or(false) {
+        fs::create_dir_all(project_location)
+            .context("unable to create project directory")?;
     }
 
     if generate_example {
-        match add_example_config(project_location, &mut units, &mut include_paths) {
-            Ok(_) => println!("Successfully generated example project structure"),
-            Err(err) => {
-                println!("Error generating example project structure: {}", err);
-                process::exit(1);
-            }
-        }
+        add_example_config(project_location, &mut units, &mut include_paths)
+            .context("unable to generate example project structure")?;
+        output::status("Successfully generated example project structure");
     }
 
     let project = ProjectConfig::new(
@@ -51,16 +47,12 @@ pub fn init(
         units,
     );
 
-    match project.save(project_location) {
-        Ok(_) => {
-            let cannon_path = project_location.canonicalize().unwrap_or(project_location.to_path_buf());
-            println!("Created a new Copper project at '{}'", cannon_path.display());
-        },
-        Err(err) => {
-            eprintln!("Unable to initialize project: {}", err);
-            process::exit(1);
-        }
-    }
+    project.save(project_location)
+        .context("unable to initialize project")?;
+    
+    let cannon_path = project_location.canonicalize().unwrap_or(project_location.to_path_buf());
+    output::status(format!("Created a new Copper project at '{}'", cannon_path.display()));
+    Ok(())
 }
 
 /// Generates an example project configuration. Creates default directories and appends example
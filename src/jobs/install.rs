@@ -0,0 +1,235 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use crate::config::{self, ProjectConfig, UnitConfig, UnitType};
+use crate::output;
+use crate::error::{Context, Result};
+use crate::file;
+use crate::compiler::Compiler;
+use super::build::{build_units, initialize_compiler, BuildSettings};
+
+/// Name of the file (within the project's Copper directory) listing all the installed files
+const INSTALL_MANIFEST_FILE_NAME: &str = "install-manifest.txt";
+
+/// Builds the specified units (or all units except the tests) and installs them together with the
+/// dynamic libraries they depend on into the standard directory layout under the prefix: binaries
+/// to `bin`, libraries to `lib` and public headers to `include`. The prefix is placed under
+/// `destdir` if it is specified. All the installed files are recorded in the install manifest,
+/// including the ones installed before a failure
+pub fn install<'a>(
+    unit_names: Option<impl Iterator<Item = &'a String>>,
+    project_location: &Path,
+    profile_name: &str,
+    prefix: &Path,
+    destdir: Option<&Path>,
+) -> Result<()> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let unit_names = match unit_names {
+        None => project.get_default_unit_names(),
+        Some(names) => names.collect(),
+    };
+    
+    build_units(&project, unit_names.clone(), profile_name, &BuildSettings::default())
+        .context("unable to build project")?;
+    // Installed targets which load the project's dynamic libraries are relinked with the
+    // installed runtime search path
+    let compiler = initialize_compiler(&project, profile_name, &BuildSettings::default())?;
+    
+    let install_root = match destdir {
+        // The absolute prefix is placed under the staging directory
+        Some(destdir) => destdir.join(prefix.strip_prefix("/").unwrap_or(prefix)),
+        None => prefix.to_path_buf(),
+    };
+    // Manifest has to stay valid regardless of where the uninstall is executed from
+    let install_root = std::path::absolute(&install_root)
+        .with_context(|| format!("resolving install directory {}", install_root.display()))?;
+    
+    let manifest_path = manifest_path(&project);
+    let mut installed = read_manifest(&manifest_path)
+        .with_context(|| format!("reading {}", manifest_path.display()))?;
+    
+    // The installed units wouldn't run without the project's dynamic libraries they load
+    let units: Vec<&UnitConfig> = project.resolve_build_order(&unit_names)?.into_iter()
+        .filter(|unit| unit_names.contains(&&unit.name) || matches!(unit.r#type, UnitType::DynamicLibrary))
+        .collect();
+    
+    let result = units.iter().try_for_each(|unit| {
+        install_unit(&project, &compiler, unit, profile_name, &install_root, &mut installed)
+            .with_context(|| format!("while installing unit '{}'", unit.name))
+    });
+    
+    // Files installed before a failure are recorded as well, so they can still be uninstalled
+    write_manifest(&manifest_path, &installed)
+        .with_context(|| format!("writing {}", manifest_path.display()))?;
+    result?;
+    
+    output::status(format!("Copper project installed to '{}'", install_root.display()));
+    Ok(())
+}
+
+/// Removes all the files recorded in the install manifest and the manifest itself
+pub fn uninstall(project_location: &Path) -> Result<()> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let manifest_path = manifest_path(&project);
+    let installed = read_manifest(&manifest_path)
+        .with_context(|| format!("reading {}", manifest_path.display()))?;
+    
+    if installed.is_empty() {
+        output::status("Nothing to uninstall");
+        return Ok(());
+    }
+    
+    for path in &installed {
+        // Files which were already removed by hand are skipped
+        if path.symlink_metadata().is_err() {
+            continue;
+        }
+        
+        fs::remove_file(path)
+            .with_context(|| format!("removing {}", path.display()))?;
+        output::status(format!("Removed '{}'", path.display()));
+    }
+    
+    fs::remove_file(&manifest_path)
+        .with_context(|| format!("removing {}", manifest_path.display()))?;
+    
+    output::status(format!("Uninstalled {} files", installed.len()));
+    Ok(())
+}
+
+/// Installs the unit's output file, library symlinks and public headers. The output file is
+/// relinked rather than copied if its runtime search path differs once installed. Every installed
+/// file is added to `installed` as soon as it is in place
+fn install_unit(
+    project: &ProjectConfig,
+    compiler: &Compiler,
+    unit: &UnitConfig,
+    profile_name: &str,
+    install_root: &Path,
+    installed: &mut BTreeSet<PathBuf>,
+) -> Result<()> {
+    let directory = match unit.r#type {
+        UnitType::Binary => install_root.join(config::INSTALL_BINARY_DIRECTORY_NAME),
+        UnitType::StaticLibrary | UnitType::DynamicLibrary => install_root.join(config::INSTALL_LIBRARY_DIRECTORY_NAME),
+        UnitType::Test => {
+            output::status(format!("Skipping test unit '{}'", unit.name));
+            return Ok(());
+        }
+    };
+    
+    let output_file = unit.get_output_file(project, profile_name);
+    if let Some(file_name) = output_file.file_name() {
+        let destination = directory.join(file_name);
+        let relinked = match unit.get_target_information(project, profile_name)? {
+            Some(target) => compiler.link_for_install(&target, &destination)?,
+            None => false,
+        };
+        if relinked {
+            output::status(format!("Installed '{}'", destination.display()));
+        } else {
+            install_file(&output_file, &destination)?;
+        }
+        installed.insert(destination);
+    }
+    
+    for (link, original) in unit.get_library_symlinks(&directory) {
+        if link.symlink_metadata().is_ok() {
+            fs::remove_file(&link)?;
+        }
+        file::create_symlink(&original, &link)?;
+        output::status(format!("Installed '{}'", link.display()));
+        installed.insert(link);
+    }
+    
+    let include_directory = install_root.join("include");
+    for header_path in unit.get_public_headers() {
+        let source = project.project_location.join(header_path);
+        
+        if source.is_dir() {
+            for header in collect_files(&source)? {
+                let destination = include_directory.join(header.strip_prefix(&source).unwrap_or(&header));
+                install_file(&header, &destination)?;
+                installed.insert(destination);
+            }
+        } else if let Some(file_name) = source.file_name() {
+            let destination = include_directory.join(file_name);
+            install_file(&source, &destination)?;
+            installed.insert(destination);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Copies the file to the destination, creating all the parent directories and replacing the
+/// existing file
+fn install_file(source: &Path, destination: &Path) -> io::Result<()> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if destination.symlink_metadata().is_ok() {
+        fs::remove_file(destination)?;
+    }
+    
+    fs::copy(source, destination).map_err(|err| io::Error::new(
+        err.kind(),
+        format!("unable to copy '{}' to '{}': {}", source.display(), destination.display(), err)
+    ))?;
+    output::status(format!("Installed '{}'", destination.display()));
+    
+    Ok(())
+}
+
+/// Recursively collects all the files within the directory
+fn collect_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        
+        if path.is_dir() {
+            files.extend(collect_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    
+    Ok(files)
+}
+
+/// Returns the location of the project's install manifest
+fn manifest_path(project: &ProjectConfig) -> PathBuf {
+    project.project_location
+        .join(config::PROJECT_DIRECTORY_NAME)
+        .join(INSTALL_MANIFEST_FILE_NAME)
+}
+
+/// Reads the paths of the installed files (one per line) from the manifest. Returns an empty set
+/// if nothing was installed yet
+fn read_manifest(manifest_path: &Path) -> io::Result<BTreeSet<PathBuf>> {
+    if !manifest_path.exists() {
+        return Ok(BTreeSet::new());
+    }
+    
+    let data = file::read_file(manifest_path)?;
+    Ok(data.lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// Writes the paths of the installed files to the manifest
+fn write_manifest(manifest_path: &Path, installed: &BTreeSet<PathBuf>) -> io::Result<()> {
+    if let Some(parent) = manifest_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    
+    let data = installed.iter()
+        .map(|path| format!("{}\n", path.display()))
+        .collect();
+    file::write_file(manifest_path, data)
+}
@@ -1,6 +1,16 @@
 mod init;
 mod build;
+mod compdb;
+mod clean;
+mod run;
+mod test;
+mod install;
 pub mod new;
 
 pub use init::init;
-pub use build::build;
\ No newline at end of file
+pub use build::{build, BuildSettings};
+pub use compdb::compdb;
+pub use clean::clean;
+pub use run::run;
+pub use test::test;
+pub use install::{install, uninstall};
\ No newline at end of file
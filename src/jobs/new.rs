@@ -1,26 +1,18 @@
 use std::path::{Path, PathBuf};
-use std::process::exit;
 use crate::config::{ProjectConfig, UnitType};
+use crate::output;
+use crate::error::{Context, Result};
 
-pub fn new_unit(project_location: &Path, unit_name: &str, unit_type: UnitType, unit_source: PathBuf) {
-    let project = ProjectConfig::import(project_location);
+pub fn new_unit(project_location: &Path, unit_name: &str, unit_type: UnitType, unit_source: PathBuf) -> Result<()> {
+    let mut project = ProjectConfig::import(project_location)
+        .context("unable to import project file")?;
 
-    match project {
-        Ok(mut project) => {
-            project.add_unit(unit_name.to_string(), unit_type.clone(), unit_source);
+    project.add_unit(unit_name.to_string(), unit_type.clone(), unit_source)
+        .context("unable to add unit")?;
 
-            if let Err(err) = project.save(project_location) {
-                println!("Unable to save project file");
-                eprintln!("{}", err);
-                exit(1);
-            }
-        },
-        Err(err) => {
-            println!("Unable to import project file");
-            eprintln!("{}", err);
-            exit(1);
-        }
-    }
+    project.save(project_location)
+        .context("unable to save project file")?;
 
-    println!("Successfully added unit \"{}\"", unit_name);
-}
\ No newline at end of file
+    output::status(format!("Successfully added unit \"{}\"", unit_name));
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use std::path::Path;
+use std::process::{ExitCode, ExitStatus};
+use crate::config::ProjectConfig;
+use crate::error::{Context, Result};
+use super::build::{build_units, BuildSettings};
+
+/// Builds the binary unit (with all of its dependencies) and executes it with the run
+/// configuration of the unit followed by the provided arguments. Returns the exit code of the
+/// executed process
+pub fn run(
+    unit_name: Option<&str>,
+    project_location: &Path,
+    profile_name: &str,
+    jobs: Option<usize>,
+    args: Vec<String>,
+) -> Result<ExitCode> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let unit = project.find_run_unit(unit_name)?;
+    
+    build_units(&project, vec![&unit.name], profile_name, &BuildSettings { jobs, ..Default::default() })
+        .context("unable to build project")?;
+    
+    let executable = unit.get_output_file(&project, profile_name);
+    // The executable has to be found regardless of the working directory it is run in
+    let executable = executable.canonicalize()
+        .with_context(|| format!("locating executable {}", executable.display()))?;
+    
+    let status = unit.get_run_command(&project, &executable)
+        .args(&args)
+        .status()
+        .with_context(|| format!("executing {}", executable.display()))?;
+    
+    Ok(exit_code(status))
+}
+
+/// Converts the exit status of the executed process into the exit code of Copper. Processes
+/// terminated by a signal are reported with the conventional `128 + signal` code
+fn exit_code(status: ExitStatus) -> ExitCode {
+    if let Some(code) = status.code() {
+        return ExitCode::from(code as u8);
+    }
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return ExitCode::from(128u8.wrapping_add(signal as u8));
+        }
+    }
+    
+    ExitCode::FAILURE
+}
@@ -0,0 +1,194 @@
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, ExitCode, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::config::{ProjectConfig, UnitConfig};
+use crate::output;
+use crate::error::{exit_code, Context, Result};
+use crate::compiler;
+use super::build::{build_units, BuildSettings};
+
+/// Interval between the checks whether a running test has finished
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Builds the test units (optionally filtered by name) and runs them in parallel. Output of the
+/// tests is captured and shown only for the failed ones. Returns a nonzero exit code if any of the
+/// tests has failed
+pub fn test(
+    filter: Option<&str>,
+    project_location: &Path,
+    profile_name: &str,
+    jobs: Option<usize>,
+    default_timeout: u64,
+) -> Result<ExitCode> {
+    let project = ProjectConfig::import(project_location)
+        .context("unable to import project")?;
+    
+    let tests = project.get_test_units(filter);
+    let filtered_out = project.get_test_units(None).len() - tests.len();
+    
+    if tests.is_empty() {
+        println!("No tests to run ({} filtered out)", filtered_out);
+        return Ok(ExitCode::SUCCESS);
+    }
+    
+    let test_names = tests.iter().map(|unit| &unit.name).collect();
+    build_units(&project, test_names, profile_name, &BuildSettings { jobs, ..Default::default() })
+        .context("unable to build tests")?;
+    
+    let jobs = jobs.unwrap_or_else(compiler::available_jobs);
+    output::status(format!("Running {} tests", tests.len()));
+    
+    let next_test = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut failures = Vec::new();
+    let mut passed = 0;
+    
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(tests.len()) {
+            let sender = sender.clone();
+            let (next_test, tests, project) = (&next_test, &tests, &project);
+            
+            scope.spawn(move || {
+                while let Some(unit) = tests.get(next_test.fetch_add(1, Ordering::SeqCst)) {
+                    let outcome = run_test(project, unit, profile_name, default_timeout);
+                    if sender.send((unit, outcome)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        
+        // Tests are reported in the order they finish
+        for (unit, outcome) in receiver {
+            match outcome {
+                Ok(outcome) if outcome.passed() => {
+                    output::status(format!("test {} ... ok ({:.2}s)", unit.name, outcome.duration.as_secs_f64()));
+                    passed += 1;
+                },
+                Ok(outcome) => {
+                    println!("test {} ... FAILED ({})", unit.name, outcome.describe_failure());
+                    failures.push((unit, outcome.output));
+                },
+                Err(err) => {
+                    println!("test {} ... FAILED ({})", unit.name, err);
+                    failures.push((unit, String::new()));
+                },
+            }
+        }
+    });
+    
+    for (unit, output) in &failures {
+        if !output.is_empty() {
+            println!("\n---- {} output ----\n{}", unit.name, output.trim_end());
+        }
+    }
+    
+    let result = if failures.is_empty() { "ok" } else { "FAILED" };
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} filtered out",
+        result, passed, failures.len(), filtered_out
+    );
+    
+    if failures.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(exit_code::TEST_FAILURE))
+    }
+}
+
+/// Outcome of a single executed test
+struct TestOutcome {
+    /// Exit status of the test, which is `None` if the test has timed out and was killed
+    status: Option<ExitStatus>,
+    duration: Duration,
+    /// Captured standard output and error of the test
+    output: String,
+}
+
+impl TestOutcome {
+    fn passed(&self) -> bool {
+        self.status.is_some_and(|status| status.success())
+    }
+    
+    fn describe_failure(&self) -> String {
+        match self.status {
+            Some(status) => status.to_string(),
+            None => format!("timed out after {}s", self.duration.as_secs()),
+        }
+    }
+}
+
+/// Executes the test unit with its run configuration and waits for it to finish or time out
+fn run_test(project: &ProjectConfig, unit: &UnitConfig, profile_name: &str, default_timeout: u64) -> Result<TestOutcome> {
+    let executable = unit.get_output_file(project, profile_name);
+    let executable = executable.canonicalize()
+        .with_context(|| format!("locating executable {}", executable.display()))?;
+    
+    let mut command = unit.get_run_command(project, &executable);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(unit.timeout.unwrap_or(default_timeout));
+    let mut child = command.spawn()
+        .with_context(|| format!("executing {}", executable.display()))?;
+    
+    // Output is read on separate threads, so the test doesn't block on a full pipe
+    let stdout = capture(child.stdout.take());
+    let stderr = capture(child.stderr.take());
+    
+    let mut status = wait_with_timeout(&mut child, deadline)
+        .with_context(|| format!("waiting for {}", executable.display()))?;
+    
+    // Processes spawned by the test may keep the pipes open after the test itself has exited (or
+    // was killed), so the output is read only until the deadline and the test times out otherwise
+    let mut output = String::new();
+    for pipe in [stdout, stderr] {
+        match pipe.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(captured) => output.push_str(&captured),
+            Err(_) => status = None,
+        }
+    }
+    let duration = start.elapsed();
+    
+    Ok(TestOutcome { status, duration, output })
+}
+
+/// Waits for the process to exit. Kills the process and returns `None` if it doesn't exit before
+/// the deadline
+fn wait_with_timeout(child: &mut Child, deadline: Instant) -> std::io::Result<Option<ExitStatus>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Reads the whole pipe on a separate thread, which sends the output once the pipe is closed. The
+/// thread is left behind if the output is not received
+fn capture(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        let _ = sender.send(String::from_utf8_lossy(&output).to_string());
+    });
+    receiver
+}
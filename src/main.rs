@@ -1,3 +1,4 @@
+use std::process::ExitCode;
 use clap::CommandFactory;
 
 mod file;
@@ -6,9 +7,16 @@ mod error;
 mod cli;
 mod config;
 mod jobs;
+mod output;
 
-fn main() {
+fn main() -> ExitCode {
     let cli_command = cli::command::Cli::command();
     
-    cli::match_args(cli_command.get_matches());
+    match cli::match_args(cli_command.get_matches()) {
+        Ok(exit_code) => exit_code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
 }
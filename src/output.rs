@@ -0,0 +1,41 @@
+//! Global output settings, which control how much information is printed by the program
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Level of detail of the printed information
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    /// Only errors and warnings are printed
+    Quiet,
+    /// Concise progress messages are printed
+    Normal,
+    /// Executed commands are printed as well
+    Verbose,
+    /// Working directory and environment of the executed commands are printed as well
+    VeryVerbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Sets the verbosity for the rest of the program's lifetime
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Returns the current verbosity
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        2 => Verbosity::Verbose,
+        _ => Verbosity::VeryVerbose,
+    }
+}
+
+/// Prints the status message unless the output is quiet
+pub fn status(message: impl Display) {
+    if verbosity() >= Verbosity::Normal {
+        println!("{}", message);
+    }
+}